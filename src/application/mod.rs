@@ -3,6 +3,9 @@ use slint::Model;
 use crate::maze::{MazeCellStatus, MazeCellType, MazeShape};
 use crate::{MainWindow, MazeCellProperty};
 
+/// Thickness of the walls relative to the cell size in the thin-wall style.
+const WALL_THICKNESS_RATIO: f32 = 0.15;
+
 impl MainWindow {
     pub(crate) fn empty_maze_window(
         config: &AppConfig,
@@ -19,17 +22,78 @@ impl MainWindow {
         handle.set_app_title(title.into());
 
         // Set a maze shape.
-        handle.set_maze_rows(maze_rows as i32);
-        handle.set_maze_cols(maze_cols as i32);
-        handle.set_cell_size(config.calc_cell_size());
+        handle.resize_maze(config);
         handle.set_margin(margin);
+        handle.set_new_maze_rows(maze_rows as i32);
+        handle.set_new_maze_cols(maze_cols as i32);
 
         Ok(handle)
     }
 
+    /// Sets the shape and the style of the drawn maze and the largest cell size fitting it.
+    pub(crate) fn resize_maze(&self, config: &AppConfig) {
+        self.set_maze_rows(config.maze_rows as i32);
+        self.set_maze_cols(config.maze_cols as i32);
+        self.set_cell_size(config.calc_cell_size());
+        self.set_thin_walls(config.thin_walls);
+        self.set_wall_thickness(config.calc_wall_thickness());
+    }
+
     pub(crate) fn redraw_cell(&self, cell_idx: usize, status: MazeCellStatus) {
-        self.get_properties_of_cells()
-            .set_row_data(cell_idx, MazeCellProperty::from_status(status));
+        let properties_of_cells = self.get_properties_of_cells();
+
+        // The terrain, the walls and the junction graph do not change during the search,
+        // and the guards are moved only by `set_cell_occupied`.
+        let new_property = MazeCellProperty::from_status(status);
+        let new_property = match properties_of_cells.row_data(cell_idx) {
+            Some(property) => MazeCellProperty {
+                occupied: property.occupied,
+                terrain: property.terrain,
+                walls: property.walls,
+                graph_node: property.graph_node,
+                graph_links: property.graph_links,
+                graph_weight: property.graph_weight,
+                ..new_property
+            },
+            None => new_property,
+        };
+
+        properties_of_cells.set_row_data(cell_idx, new_property);
+    }
+
+    /// Draws the part of the junction graph in the cell.
+    ///
+    /// `links` has the bit `1 << i` set for a link toward the neighbor in the direction of index `i`,
+    /// and a weight of zero is not drawn.
+    pub(crate) fn set_cell_graph(&self, cell_idx: usize, node: bool, links: i32, weight: i32) {
+        let properties_of_cells = self.get_properties_of_cells();
+
+        if let Some(mut property) = properties_of_cells.row_data(cell_idx) {
+            property.graph_node = node;
+            property.graph_links = links;
+            property.graph_weight = weight;
+            properties_of_cells.set_row_data(cell_idx, property);
+        }
+    }
+
+    /// Draws the walls on the sides of the cell, given in the order of `ADJACENT_DISPLACEMENT`.
+    pub(crate) fn set_cell_walls(&self, cell_idx: usize, walls: [bool; 4]) {
+        let properties_of_cells = self.get_properties_of_cells();
+
+        if let Some(mut property) = properties_of_cells.row_data(cell_idx) {
+            property.walls = MazeCellProperty::encode_walls(walls);
+            properties_of_cells.set_row_data(cell_idx, property);
+        }
+    }
+
+    /// Shows or hides a guard in the cell.
+    pub(crate) fn set_cell_occupied(&self, cell_idx: usize, occupied: bool) {
+        let properties_of_cells = self.get_properties_of_cells();
+
+        if let Some(mut property) = properties_of_cells.row_data(cell_idx) {
+            property.occupied = occupied;
+            properties_of_cells.set_row_data(cell_idx, property);
+        }
     }
 }
 
@@ -47,6 +111,16 @@ impl MazeCellProperty {
         Self {
             cell_color: color,
             footprint: false,
+            occupied: false,
+            on_path: false,
+            show_heat: false,
+            heat: 0.0,
+            terrain: 0.0,
+            walls: 0,
+            direction: -1,
+            graph_node: false,
+            graph_links: 0,
+            graph_weight: 0,
         }
     }
 
@@ -54,8 +128,28 @@ impl MazeCellProperty {
         Self {
             cell_color: status.cell_color(),
             footprint: status.footprint,
+            occupied: false,
+            on_path: status.path_rank.is_some(),
+            show_heat: status.heat.is_some(),
+            heat: status.heat.unwrap_or(0.0),
+            terrain: 0.0,
+            walls: 0,
+            direction: status.direction.map_or(-1, |direction| direction as i32),
+            graph_node: false,
+            graph_links: 0,
+            graph_weight: 0,
         }
     }
+
+    /// Returns the walls on the sides of a cell with the bit `1 << i` set for a wall in the direction of index `i`.
+    pub(crate) fn encode_walls(walls: [bool; 4]) -> i32 {
+        walls
+            .iter()
+            .enumerate()
+            .filter(|&(_, &wall)| wall)
+            .map(|(direction, _)| 1 << direction)
+            .sum()
+    }
 }
 
 #[derive(Debug)]
@@ -67,13 +161,34 @@ pub(crate) struct AppConfig {
     pub(crate) maze_cols: usize,
     pub(crate) max_cell_size: f32,
     pub(crate) margin: f32,
+
+    /// Whether the maze is drawn in the thin-wall style,
+    /// where the cells are rooms with walls drawn as thin lines on the edges between them.
+    pub(crate) thin_walls: bool,
 }
 
 impl AppConfig {
     pub(crate) fn calc_cell_size(&self) -> f32 {
-        self.max_cell_size
-            .min(self.max_maze_height as f32 / self.maze_rows as f32)
-            .min(self.max_maze_width as f32 / self.maze_cols as f32)
+        if self.thin_walls {
+            // A line of `n` rooms is drawn with `n + 1` walls including both ends.
+            let calc_room_size = |max_len: usize, num_rooms: usize| {
+                let num_rooms = num_rooms as f32;
+                max_len as f32 / (num_rooms + (num_rooms + 1.0) * WALL_THICKNESS_RATIO)
+            };
+
+            self.max_cell_size
+                .min(calc_room_size(self.max_maze_height, self.maze_rows))
+                .min(calc_room_size(self.max_maze_width, self.maze_cols))
+        } else {
+            self.max_cell_size
+                .min(self.max_maze_height as f32 / self.maze_rows as f32)
+                .min(self.max_maze_width as f32 / self.maze_cols as f32)
+        }
+    }
+
+    /// Returns the thickness of the walls in the thin-wall style.
+    pub(crate) fn calc_wall_thickness(&self) -> f32 {
+        self.calc_cell_size() * WALL_THICKNESS_RATIO
     }
 
     pub(crate) fn maze_shape(&self) -> MazeShape {
@@ -1,7 +1,10 @@
 slint::include_modules!();
 
 use application::AppConfig;
+use maze::analysis::analyze_maze;
 use maze::{generate_maze, searcher::dfs::DFSSearcher};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
 use slint::ComponentHandle;
 use visualizer::Visualizer;
 
@@ -18,26 +21,157 @@ const MAZE_COLS: usize = 25;
 const CELL_SIZE: f32 = 32.0;
 const MARGIN: f32 = 1.0;
 
+const USAGE: &str = "Usage: maze-search-visualizer [seed]\n       maze-search-visualizer --stats [seed] [rows cols]";
+
+/// Options given on the command line.
+struct CliArgs {
+    stats_only: bool,
+    seed: Option<u64>,
+    maze_rows: usize,
+    maze_cols: usize,
+}
+
+/// Parses the command line arguments, reporting the first one which is not valid.
+fn parse_args(args: impl IntoIterator<Item = String>) -> Result<CliArgs, String> {
+    let (flags, values): (Vec<_>, Vec<_>) = args.into_iter().partition(|arg| arg.starts_with("--"));
+
+    let mut stats_only = false;
+    for flag in flags {
+        match flag.as_str() {
+            "--stats" => stats_only = true,
+            _ => return Err(format!("Unknown option: {}", flag)),
+        }
+    }
+
+    let max_values = if stats_only { 3 } else { 1 };
+    if values.len() > max_values {
+        return Err(format!("Unexpected argument: {}", values[max_values]));
+    }
+
+    let seed = values
+        .first()
+        .map(|arg| arg.parse().map_err(|_| format!("Invalid seed: {}", arg)))
+        .transpose()?;
+
+    let (maze_rows, maze_cols) = match &values[..] {
+        [] | [_] => (MAZE_ROWS, MAZE_COLS),
+        [_, _] => {
+            return Err("The number of columns must be given with the number of rows.".to_string())
+        }
+        [_, rows, cols] => {
+            let parse_size = |arg: &String| match arg.parse::<usize>() {
+                Ok(size) if size % 2 == 1 => Ok(size),
+                _ => Err(format!(
+                    "Invalid maze size (must be an odd number): {}",
+                    arg
+                )),
+            };
+            let size = (parse_size(rows)?, parse_size(cols)?);
+            if size == (1, 1) {
+                return Err("The maze must contain multiple squares.".to_string());
+            }
+            size
+        }
+        _ => unreachable!(),
+    };
+
+    Ok(CliArgs {
+        stats_only,
+        seed,
+        maze_rows,
+        maze_cols,
+    })
+}
+
+/// Prints the statistics of a maze of each generator made from the seed, without opening the window.
+fn print_generator_statistics(config: &AppConfig, seed: u64) {
+    println!("Seed: {}", seed);
+
+    for algorithm in generate_maze::GENERATION_ALGORITHMS {
+        let generation_config = generate_maze::GenerationConfig {
+            algorithm: algorithm.to_string(),
+            ..Default::default()
+        };
+        let maze = generate_maze::generate_maze(
+            config.maze_shape(),
+            &generation_config,
+            &mut ChaCha8Rng::seed_from_u64(seed),
+        );
+
+        println!("\n[{}]\n{}", algorithm, analyze_maze(&maze));
+    }
+}
+
 fn main() -> Result<(), slint::PlatformError> {
-    let mut rng = rand::thread_rng();
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(message) => {
+            eprintln!("{}\n{}", message, USAGE);
+            std::process::exit(2);
+        }
+    };
+    let seed = args.seed.unwrap_or_else(rand::random);
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
 
     let config = AppConfig {
         title: APP_TITLE.to_string(),
         max_maze_height: MAX_MAZE_HEIGHT,
         max_maze_width: MAX_MAZE_WIDTH,
-        maze_rows: MAZE_ROWS,
-        maze_cols: MAZE_COLS,
+        maze_rows: args.maze_rows,
+        maze_cols: args.maze_cols,
         max_cell_size: CELL_SIZE,
         margin: MARGIN,
+        thin_walls: false,
     };
 
+    if args.stats_only {
+        print_generator_statistics(&config, seed);
+        return Ok(());
+    }
+
     let handle = MainWindow::empty_maze_window(&config)?;
     let handle_weak = handle.as_weak();
 
-    let init_maze = generate_maze::generate_maze(config.maze_shape(), &mut rng);
+    let init_maze = generate_maze::generate_maze(
+        config.maze_shape(),
+        &generate_maze::GenerationConfig::default(),
+        &mut rng,
+    );
     let dfs_searcher = DFSSearcher::new(init_maze.clone());
 
-    Visualizer::new(Box::new(dfs_searcher), handle_weak);
+    Visualizer::new(Box::new(dfs_searcher), seed, config, handle_weak);
 
     handle.run()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<CliArgs, String> {
+        parse_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let args = parse(&["12345"]).unwrap();
+        assert!(!args.stats_only);
+        assert_eq!(args.seed, Some(12345));
+
+        let args = parse(&["--stats"]).unwrap();
+        assert!(args.stats_only);
+        assert_eq!(args.seed, None);
+        assert_eq!((args.maze_rows, args.maze_cols), (MAZE_ROWS, MAZE_COLS));
+
+        let args = parse(&["--stats", "7", "11", "31"]).unwrap();
+        assert_eq!(args.seed, Some(7));
+        assert_eq!((args.maze_rows, args.maze_cols), (11, 31));
+
+        assert!(parse(&["seed"]).is_err());
+        assert!(parse(&["--stat"]).is_err());
+        assert!(parse(&["7", "11", "31"]).is_err());
+        assert!(parse(&["--stats", "7", "11"]).is_err());
+        assert!(parse(&["--stats", "7", "10", "31"]).is_err());
+        assert!(parse(&["--stats", "7", "11", "31", "1"]).is_err());
+    }
+}
@@ -0,0 +1,351 @@
+use std::fmt;
+use std::ops::RangeInclusive;
+
+use itertools::Itertools;
+use ndarray::prelude::*;
+
+use super::searcher::dfs::DFSSearcher;
+use super::searcher::MazeSearcher;
+use super::{MazeCellType, MazeGrid};
+
+/// Statistics characterizing the layout of a maze.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct MazeStatistics {
+    /// Number of steps on the shortest path from the start to the goal, if the goal can be reached.
+    pub(crate) solution_length: Option<usize>,
+
+    /// Number of passable cells.
+    pub(crate) passable_cells: usize,
+
+    /// Number of passages with only one passable neighbor.
+    pub(crate) dead_ends: usize,
+
+    /// Number of passable cells with three or more passable neighbors.
+    pub(crate) junctions: usize,
+
+    /// Average number of ways leading on from the passable cells other than dead ends, not counting the way back.
+    pub(crate) branching_factor: f64,
+
+    /// Number of cells in the longest corridor, which is a connected run of cells with exactly two passable neighbors.
+    pub(crate) longest_corridor: usize,
+
+    /// Number of independent loops, which is zero for a perfect maze.
+    pub(crate) loops: usize,
+
+    /// Average number of steps from each dead end to the first junction.
+    ///
+    /// A maze with a high river factor has a few long dead ends, and one with a low factor has many short ones.
+    pub(crate) river_factor: f64,
+}
+
+impl fmt::Display for MazeStatistics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.solution_length {
+            Some(solution_length) => writeln!(f, "Solution length: {}", solution_length)?,
+            None => writeln!(f, "Solution length: none")?,
+        }
+        writeln!(f, "Passable squares: {}", self.passable_cells)?;
+        writeln!(f, "Dead ends: {}", self.dead_ends)?;
+        writeln!(f, "Junctions: {}", self.junctions)?;
+        writeln!(f, "Branching factor: {:.2}", self.branching_factor)?;
+        writeln!(f, "Longest corridor: {}", self.longest_corridor)?;
+        writeln!(f, "Loops: {}", self.loops)?;
+        write!(f, "River factor: {:.2}", self.river_factor)
+    }
+}
+
+/// Measures of how hard it is to solve a maze.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct DifficultyMetrics {
+    /// Number of cells on the shortest path from the start to the goal, divided by the number of passable cells.
+    pub(crate) solution_length_ratio: f64,
+
+    /// Number of cells on the shortest path where more than one way leads on.
+    pub(crate) decision_points: usize,
+
+    /// Number of passages with only one passable neighbor.
+    pub(crate) dead_ends: usize,
+
+    /// Number of cells visited by DFS until it reaches the goal.
+    pub(crate) dfs_expansions: usize,
+}
+
+/// Ranges of the difficulty metrics which a maze is generated to fall in.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct DifficultyTarget {
+    pub(crate) solution_length_ratio: RangeInclusive<f64>,
+    pub(crate) decision_points: RangeInclusive<usize>,
+    pub(crate) dead_ends: RangeInclusive<usize>,
+    pub(crate) dfs_expansions: RangeInclusive<usize>,
+}
+
+impl Default for DifficultyTarget {
+    fn default() -> Self {
+        Self {
+            solution_length_ratio: 0.0..=1.0,
+            decision_points: 0..=usize::MAX,
+            dead_ends: 0..=usize::MAX,
+            dfs_expansions: 0..=usize::MAX,
+        }
+    }
+}
+
+/// Returns how far the value falls outside the range, relative to the bound exceeded.
+fn calculate_relative_miss(value: f64, range: RangeInclusive<f64>) -> f64 {
+    let (min, max) = range.into_inner();
+
+    if value < min {
+        (min - value) / min
+    } else if value > max {
+        (value - max) / value
+    } else {
+        0.0
+    }
+}
+
+impl DifficultyTarget {
+    /// Returns how far the metrics fall outside the target, which is zero if all of them are in range.
+    ///
+    /// The miss of each metric is relative to the bound it exceeds, so that the metrics are comparable.
+    pub(crate) fn miss(&self, metrics: &DifficultyMetrics) -> f64 {
+        let as_f64 = |range: &RangeInclusive<usize>| *range.start() as f64..=*range.end() as f64;
+
+        [
+            calculate_relative_miss(
+                metrics.solution_length_ratio,
+                self.solution_length_ratio.clone(),
+            ),
+            calculate_relative_miss(
+                metrics.decision_points as f64,
+                as_f64(&self.decision_points),
+            ),
+            calculate_relative_miss(metrics.dead_ends as f64, as_f64(&self.dead_ends)),
+            calculate_relative_miss(metrics.dfs_expansions as f64, as_f64(&self.dfs_expansions)),
+        ]
+        .iter()
+        .sum()
+    }
+
+    /// Returns whether all the metrics fall in range.
+    pub(crate) fn contains(&self, metrics: &DifficultyMetrics) -> bool {
+        self.miss(metrics) == 0.0
+    }
+}
+
+/// Counts the passable cells next to the cell.
+pub(crate) fn count_passable_neighbors(maze: &MazeGrid, coord: (usize, usize)) -> usize {
+    maze.passable_neighbors(coord).count()
+}
+
+/// Returns whether the cell is a passage with only one passable neighbor.
+fn is_dead_end(maze: &MazeGrid, coord: (usize, usize)) -> bool {
+    maze.cells[coord] == MazeCellType::Passage && count_passable_neighbors(maze, coord) == 1
+}
+
+/// Returns the sizes of the connected regions of the cells satisfying `is_member`.
+fn measure_regions<F>(maze: &MazeGrid, is_member: F) -> Vec<usize>
+where
+    F: Fn((usize, usize)) -> bool,
+{
+    let mut visited = Array2::from_elem((maze.shape.rows, maze.shape.cols), false);
+    let mut region_sizes = vec![];
+
+    for (origin, _) in maze.cells.indexed_iter() {
+        if visited[origin] || !maze.cells[origin].is_passable() || !is_member(origin) {
+            continue;
+        }
+
+        let dist_grid = maze
+            .shape
+            .dist_grid_from(origin, |from, to| maze.can_move(from, to) && is_member(to));
+        let mut region_size = 0;
+        for (is_visited, dist) in visited.iter_mut().zip(&dist_grid) {
+            if dist.is_some() {
+                *is_visited = true;
+                region_size += 1;
+            }
+        }
+
+        region_sizes.push(region_size);
+    }
+
+    region_sizes
+}
+
+/// Returns the number of steps from the dead end to the first cell which is not in a corridor.
+fn measure_dead_end_branch(maze: &MazeGrid, dead_end: (usize, usize)) -> usize {
+    let mut prev_coord = dead_end;
+    let mut coord = maze.passable_neighbors(dead_end).next().unwrap();
+    let mut length = 1;
+
+    while count_passable_neighbors(maze, coord) == 2 {
+        let next_coord = maze
+            .passable_neighbors(coord)
+            .find(|&adj_coord| adj_coord != prev_coord)
+            .unwrap();
+        prev_coord = coord;
+        coord = next_coord;
+        length += 1;
+    }
+
+    length
+}
+
+/// Calculates the statistics of the layout of the maze.
+pub(crate) fn analyze_maze(maze: &MazeGrid) -> MazeStatistics {
+    let passable_coords = maze
+        .cells
+        .indexed_iter()
+        .filter(|(_, cell_type)| cell_type.is_passable())
+        .map(|(coord, _)| coord)
+        .collect_vec();
+    let degree = |coord| count_passable_neighbors(maze, coord);
+
+    let dead_ends = passable_coords
+        .iter()
+        .filter(|&&coord| is_dead_end(maze, coord))
+        .collect_vec();
+    let junctions = passable_coords
+        .iter()
+        .filter(|&&coord| degree(coord) >= 3)
+        .count();
+
+    let branching_degrees = passable_coords
+        .iter()
+        .map(|&coord| degree(coord))
+        .filter(|&degree| degree >= 2)
+        .collect_vec();
+    let branching_factor = if branching_degrees.is_empty() {
+        0.0
+    } else {
+        let num_ways_on = branching_degrees
+            .iter()
+            .map(|degree| degree - 1)
+            .sum::<usize>();
+        num_ways_on as f64 / branching_degrees.len() as f64
+    };
+
+    let longest_corridor = measure_regions(maze, |coord| {
+        maze.cells[coord].is_passable() && degree(coord) == 2
+    })
+    .into_iter()
+    .max()
+    .unwrap_or(0);
+
+    // Each loop adds one edge to a spanning forest of the passable cells.
+    let num_edges = passable_coords
+        .iter()
+        .map(|&coord| degree(coord))
+        .sum::<usize>()
+        / 2;
+    let num_regions = measure_regions(maze, |coord| maze.cells[coord].is_passable()).len();
+    let loops = num_edges + num_regions - passable_coords.len();
+
+    let river_factor = if dead_ends.is_empty() {
+        0.0
+    } else {
+        let total_length = dead_ends
+            .iter()
+            .map(|&&dead_end| measure_dead_end_branch(maze, dead_end))
+            .sum::<usize>();
+        total_length as f64 / dead_ends.len() as f64
+    };
+
+    MazeStatistics {
+        solution_length: maze.dist_grid_from(maze.start)[maze.goal],
+        passable_cells: passable_coords.len(),
+        dead_ends: dead_ends.len(),
+        junctions,
+        branching_factor,
+        longest_corridor,
+        loops,
+        river_factor,
+    }
+}
+
+/// Measures the difficulty of the maze.
+/// Returns `None` if the goal cannot be reached from the start.
+pub(crate) fn measure_difficulty(maze: &MazeGrid) -> Option<DifficultyMetrics> {
+    let path = maze.find_shortest_path(maze.start, maze.goal)?;
+    let num_passable = maze.cells.iter().filter(|cell| cell.is_passable()).count();
+
+    // Except at the start, the way back to the previous cell is not counted.
+    let decision_points = path[..path.len() - 1]
+        .iter()
+        .enumerate()
+        .filter(|&(i, &coord)| count_passable_neighbors(maze, coord) - usize::from(i > 0) >= 2)
+        .count();
+
+    let dead_ends = maze
+        .cells
+        .indexed_iter()
+        .filter(|&(coord, _)| is_dead_end(maze, coord))
+        .count();
+
+    let mut searcher = DFSSearcher::new(maze.clone());
+    while !searcher.terminated() {
+        searcher.advance().unwrap();
+    }
+    let dfs_expansions = searcher
+        .cell_statuses()
+        .iter()
+        .filter(|status| status.visited)
+        .count();
+
+    Some(DifficultyMetrics {
+        solution_length_ratio: path.len() as f64 / num_passable as f64,
+        decision_points,
+        dead_ends,
+        dfs_expansions,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_measure_difficulty() {
+        let maze: MazeGrid = crate::maze::TEST_MAZE.parse().unwrap();
+
+        let metrics = measure_difficulty(&maze).unwrap();
+
+        // The path turns down at the only junction, leaving a dead end at the bottom right.
+        assert_eq!(metrics.solution_length_ratio, 7.0 / 11.0);
+        assert_eq!(metrics.decision_points, 1);
+        assert_eq!(metrics.dead_ends, 1);
+        assert_eq!(metrics.dfs_expansions, 7);
+
+        let target = DifficultyTarget {
+            decision_points: 1..=3,
+            ..Default::default()
+        };
+        assert!(target.contains(&metrics));
+
+        let target = DifficultyTarget {
+            dead_ends: 2..=4,
+            ..Default::default()
+        };
+        assert_eq!(target.miss(&metrics), 0.5);
+    }
+
+    #[test]
+    fn test_analyze_maze() {
+        let maze: MazeGrid = crate::maze::TEST_MAZE.parse().unwrap();
+
+        let statistics = analyze_maze(&maze);
+
+        assert_eq!(statistics.solution_length, Some(6));
+        assert_eq!(statistics.passable_cells, 11);
+        assert_eq!(statistics.dead_ends, 1);
+        assert_eq!(statistics.junctions, 1);
+        assert_eq!(statistics.branching_factor, 9.0 / 8.0);
+        assert_eq!(statistics.longest_corridor, 3);
+        assert_eq!(statistics.loops, 0);
+        assert_eq!(statistics.river_factor, 4.0);
+
+        // Every two-by-two block of an open field closes a loop.
+        let maze: MazeGrid = "S . .\n. . .\n. . G".parse().unwrap();
+        assert_eq!(analyze_maze(&maze).loops, 4);
+    }
+}
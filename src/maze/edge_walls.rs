@@ -0,0 +1,123 @@
+use itertools::Itertools;
+use ndarray::prelude::*;
+
+use super::{MazeCellType, MazeShape, ADJACENT_DISPLACEMENT};
+
+/// Walls standing on the edges between adjacent cells, as in a classic printed maze.
+///
+/// A maze with edge walls is generated on a lattice of `2n - 1` rows and columns for `n` rows and columns of cells,
+/// whose cells at even coordinates become the cells of the maze,
+/// and whose cells between them become the walls on the edges or the openings in them.
+#[derive(Debug, Clone)]
+pub(crate) struct EdgeWalls {
+    /// Whether a wall stands on each side of each cell, indexed by the cell and the index of the direction.
+    /// The sides on the border of the maze always have walls.
+    sides: Array3<bool>,
+}
+
+impl EdgeWalls {
+    /// Returns the shape of the lattice on which a maze of the shape is generated.
+    pub(crate) fn lattice_shape(shape: MazeShape) -> MazeShape {
+        MazeShape::new(2 * shape.rows - 1, 2 * shape.cols - 1)
+    }
+
+    /// Returns the coordinate on the lattice of the cell of the maze.
+    pub(crate) fn to_lattice(coord: (usize, usize)) -> (usize, usize) {
+        (2 * coord.0, 2 * coord.1)
+    }
+
+    /// Returns the cell of the maze at the even coordinate on the lattice.
+    pub(crate) fn from_lattice(lattice_coord: (usize, usize)) -> (usize, usize) {
+        (lattice_coord.0 / 2, lattice_coord.1 / 2)
+    }
+
+    /// Returns the cells of the maze drawn differently when the cell of the lattice changes:
+    /// the cell itself at even coordinates, and the cells on both sides of an edge.
+    pub(crate) fn cells_beside(
+        lattice_coord: (usize, usize),
+    ) -> impl Iterator<Item = (usize, usize)> {
+        let (row, col) = lattice_coord;
+
+        match (row % 2, col % 2) {
+            (0, 0) => vec![(row / 2, col / 2)],
+            (1, 0) => vec![(row / 2, col / 2), (row / 2 + 1, col / 2)],
+            (0, 1) => vec![(row / 2, col / 2), (row / 2, col / 2 + 1)],
+            _ => vec![],
+        }
+        .into_iter()
+    }
+
+    /// Returns whether a wall stands on each side of the cell of the maze, in the order of `ADJACENT_DISPLACEMENT`,
+    /// reading the walls from the lattice.
+    pub(crate) fn lattice_sides(
+        lattice: &Array2<MazeCellType>,
+        coord: (usize, usize),
+    ) -> [bool; 4] {
+        let (row, col) = Self::to_lattice(coord);
+
+        ADJACENT_DISPLACEMENT.map(|(diff_row, diff_col)| {
+            let edge_coord = (row.wrapping_add(diff_row), col.wrapping_add(diff_col));
+            lattice
+                .get(edge_coord)
+                .is_none_or(|&cell_type| !cell_type.is_passable())
+        })
+    }
+
+    /// Splits the cells of the lattice into the cells of the maze and the walls on the edges between them.
+    pub(crate) fn split_lattice(lattice: &Array2<MazeCellType>) -> (Array2<MazeCellType>, Self) {
+        let (lattice_rows, lattice_cols) = lattice.dim();
+        let (rows, cols) = (lattice_rows.div_ceil(2), lattice_cols.div_ceil(2));
+
+        let cells = Array2::from_shape_fn((rows, cols), |coord| lattice[Self::to_lattice(coord)]);
+        let sides = Array3::from_shape_vec(
+            (rows, cols, ADJACENT_DISPLACEMENT.len()),
+            cells
+                .indexed_iter()
+                .flat_map(|(coord, _)| Self::lattice_sides(lattice, coord))
+                .collect_vec(),
+        )
+        .unwrap();
+
+        (cells, Self { sides })
+    }
+
+    /// Returns whether a wall stands on each side of the cell, in the order of `ADJACENT_DISPLACEMENT`.
+    pub(crate) fn sides(&self, coord: (usize, usize)) -> [bool; 4] {
+        std::array::from_fn(|direction| self.sides[(coord.0, coord.1, direction)])
+    }
+
+    /// Returns whether a wall stands between the cell and the adjacent cell.
+    /// Cells not adjacent to each other are never separated by a wall.
+    pub(crate) fn separates(&self, from: (usize, usize), to: (usize, usize)) -> bool {
+        ADJACENT_DISPLACEMENT
+            .iter()
+            .position(|&(diff_row, diff_col)| {
+                (from.0.wrapping_add(diff_row), from.1.wrapping_add(diff_col)) == to
+            })
+            .is_some_and(|direction| self.sides[(from.0, from.1, direction)])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_lattice() {
+        let lattice: Array2<MazeCellType> = crate::maze::TEST_MAZE
+            .parse::<crate::maze::MazeGrid>()
+            .unwrap()
+            .cells;
+
+        let (cells, walls) = EdgeWalls::split_lattice(&lattice);
+
+        assert_eq!(cells.dim(), (2, 3));
+        assert_eq!(cells[(0, 0)], MazeCellType::Start);
+        assert_eq!(cells[(1, 0)], MazeCellType::Goal);
+        assert!(walls.separates((0, 0), (1, 0)));
+        assert!(!walls.separates((0, 0), (0, 1)));
+        assert!(!walls.separates((0, 1), (1, 1)));
+        assert!(walls.separates((1, 1), (1, 2)));
+        assert_eq!(walls.sides((0, 2)), [true, false, true, false]);
+    }
+}
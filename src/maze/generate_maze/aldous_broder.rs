@@ -0,0 +1,34 @@
+use rand::prelude::*;
+
+use crate::maze::{MazeCellType, MazeShape};
+
+use super::{neighboring_cells, random_cell, GenerationCanvas, PartialMazeGenerator};
+
+/// Generator of a maze consisting of only passages and walls using the Aldous-Broder algorithm.
+///
+/// A random walk is taken from a random cell until all cells are visited,
+/// and the wall is dug through whenever the walk enters a cell for the first time.
+/// Every possible maze is generated with equal probability, but covering all cells takes many steps.
+pub(crate) struct AldousBroderGenerator;
+
+impl PartialMazeGenerator for AldousBroderGenerator {
+    fn generate(&self, shape: MazeShape, cells: &mut GenerationCanvas, rng: &mut dyn RngCore) {
+        let MazeShape { rows, cols } = shape;
+
+        let mut coord = random_cell(shape, rng);
+        cells.set(coord, MazeCellType::Passage);
+
+        let mut num_unvisited = rows.div_ceil(2) * cols.div_ceil(2) - 1;
+        while num_unvisited > 0 {
+            let (next_coord, wall_coord) = neighboring_cells(shape, coord).choose(rng).unwrap();
+
+            if cells[next_coord] == MazeCellType::Wall {
+                cells.set(next_coord, MazeCellType::Passage);
+                cells.set(wall_coord, MazeCellType::Passage);
+                num_unvisited -= 1;
+            }
+
+            coord = next_coord;
+        }
+    }
+}
@@ -0,0 +1,73 @@
+use rand::prelude::*;
+
+use crate::maze::{MazeCellType, MazeShape, ADJACENT_DISPLACEMENT};
+
+use super::{GenerationCanvas, PartialMazeGenerator};
+
+/// Generator of a maze consisting of only passages and walls.
+///
+/// In generating the maze, the approach used is to dig through the walls to create passages.
+/// The passage is extended from the last dug cell, and when it gets stuck,
+/// digging resumes from a random cell that may still have walls to dig through.
+pub(crate) struct BacktrackerGenerator;
+
+impl PartialMazeGenerator for BacktrackerGenerator {
+    fn generate(&self, shape: MazeShape, cells: &mut GenerationCanvas, rng: &mut dyn RngCore) {
+        let MazeShape { rows, cols } = shape;
+
+        let effective_rows = rows.div_ceil(2);
+        let effective_cols = cols.div_ceil(2);
+
+        let init_row = 2 * rng.gen_range(0..effective_rows);
+        let init_col = 2 * rng.gen_range(0..effective_cols);
+
+        let mut coordinates_pool = vec![(init_row, init_col)];
+        let mut idx = 0;
+
+        loop {
+            let coord = coordinates_pool[idx];
+            let (row, col) = coord;
+
+            cells.set(coord, MazeCellType::Passage);
+
+            let mut adjacent_diffs = ADJACENT_DISPLACEMENT;
+            adjacent_diffs.shuffle(rng);
+
+            let next_coord = adjacent_diffs.iter().find_map(|&(diff_row, diff_col)| {
+                let cand_next_row = row.wrapping_add(diff_row.wrapping_mul(2));
+                let cand_next_col = col.wrapping_add(diff_col.wrapping_mul(2));
+
+                if cand_next_row < rows
+                    && cand_next_col < cols
+                    && cells[(cand_next_row, cand_next_col)] == MazeCellType::Wall
+                {
+                    let adj_row = row.wrapping_add(diff_row);
+                    let adj_col = col.wrapping_add(diff_col);
+
+                    cells.set((adj_row, adj_col), MazeCellType::Passage);
+                    cells.set((cand_next_row, cand_next_col), MazeCellType::Passage);
+
+                    Some((cand_next_row, cand_next_col))
+                } else {
+                    None
+                }
+            });
+
+            match next_coord {
+                Some(next_coord) => {
+                    coordinates_pool.push(next_coord);
+                    idx = coordinates_pool.len() - 1;
+                }
+                None => {
+                    coordinates_pool.remove(idx);
+
+                    if coordinates_pool.is_empty() {
+                        break;
+                    }
+
+                    idx = rng.gen_range(0..coordinates_pool.len());
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use rand::prelude::*;
+
+use crate::maze::{MazeCellType, MazeShape};
+
+use super::{GenerationCanvas, PartialMazeGenerator};
+
+/// Generator of a maze consisting of only passages and walls using the binary tree algorithm.
+///
+/// Each cell is connected to the cell above or the cell to the left, chosen at random.
+/// The top row and the left column become straight corridors,
+/// and every cell has a path toward the top-left corner that never goes down or right.
+pub(crate) struct BinaryTreeGenerator;
+
+impl PartialMazeGenerator for BinaryTreeGenerator {
+    fn generate(&self, shape: MazeShape, cells: &mut GenerationCanvas, rng: &mut dyn RngCore) {
+        let MazeShape { rows, cols } = shape;
+
+        for row in (0..rows).step_by(2) {
+            for col in (0..cols).step_by(2) {
+                cells.set((row, col), MazeCellType::Passage);
+
+                let wall_coord = match (row > 0, col > 0) {
+                    (true, true) => {
+                        if rng.gen_bool(0.5) {
+                            (row - 1, col)
+                        } else {
+                            (row, col - 1)
+                        }
+                    }
+                    (true, false) => (row - 1, col),
+                    (false, true) => (row, col - 1),
+                    (false, false) => continue,
+                };
+
+                cells.set(wall_coord, MazeCellType::Passage);
+            }
+        }
+    }
+}
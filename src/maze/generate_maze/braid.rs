@@ -0,0 +1,83 @@
+use itertools::{iproduct, Itertools};
+use ndarray::prelude::*;
+use rand::prelude::*;
+
+use crate::maze::{MazeCellType, MazeShape};
+
+use super::{neighboring_cells, GenerationCanvas};
+
+/// Returns whether the cell at even coordinates is a dead end, i.e. has only one wall dug through.
+fn is_dead_end(cells: &Array2<MazeCellType>, shape: MazeShape, coord: (usize, usize)) -> bool {
+    neighboring_cells(shape, coord)
+        .filter(|&(_, wall_coord)| cells[wall_coord].is_passable())
+        .count()
+        == 1
+}
+
+/// Removes the fraction `braid_factor` of the dead ends by digging through one more of their walls,
+/// which creates loops in the maze.
+///
+/// A wall toward another dead end is preferred, so that both dead ends are removed at once.
+pub(crate) fn braid(
+    cells: &mut GenerationCanvas,
+    shape: MazeShape,
+    braid_factor: f64,
+    rng: &mut dyn RngCore,
+) {
+    let MazeShape { rows, cols } = shape;
+
+    let mut dead_ends = iproduct!((0..rows).step_by(2), (0..cols).step_by(2))
+        .filter(|&coord| is_dead_end(cells, shape, coord))
+        .collect_vec();
+    dead_ends.shuffle(rng);
+
+    let num_removed = (braid_factor.clamp(0.0, 1.0) * dead_ends.len() as f64).round() as usize;
+    dead_ends.truncate(num_removed);
+
+    for coord in dead_ends {
+        // The dead end may have been removed together with another one.
+        if !is_dead_end(cells, shape, coord) {
+            continue;
+        }
+
+        let walls = neighboring_cells(shape, coord)
+            .filter(|&(_, wall_coord)| !cells[wall_coord].is_passable())
+            .collect_vec();
+
+        let toward_dead_end = walls
+            .iter()
+            .filter(|&&(next_coord, _)| is_dead_end(cells, shape, next_coord))
+            .collect_vec();
+
+        let wall_coord = match toward_dead_end.choose(rng) {
+            Some(&&(_, wall_coord)) => wall_coord,
+            None => match walls.choose(rng) {
+                Some(&(_, wall_coord)) => wall_coord,
+                None => continue,
+            },
+        };
+
+        cells.set(wall_coord, MazeCellType::Passage);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze::generate_maze::{create_generator, GenerationConfig};
+
+    #[test]
+    fn test_braid() {
+        let shape = MazeShape::new(21, 21);
+        let mut rng = rand::thread_rng();
+
+        let mut cells = GenerationCanvas::new(shape, None);
+        create_generator(&GenerationConfig::default()).generate(shape, &mut cells, &mut rng);
+        braid(&mut cells, shape, 1.0, &mut rng);
+
+        let num_dead_ends = iproduct!((0..21).step_by(2), (0..21).step_by(2))
+            .filter(|&coord| is_dead_end(&cells, shape, coord))
+            .count();
+        assert_eq!(num_dead_ends, 0);
+    }
+}
@@ -0,0 +1,136 @@
+use itertools::iproduct;
+use ndarray::prelude::*;
+use rand::prelude::*;
+
+use crate::maze::{MazeCellType, MazeShape};
+
+use super::{keep_largest_region, GenerationCanvas, PartialMazeGenerator};
+
+/// Probability of a cell being a wall in the initial random fill.
+const INIT_WALL_PROBABILITY: f64 = 0.45;
+
+/// Number of times the birth and survival rules are applied.
+const NUM_SMOOTHING_STEPS: usize = 5;
+
+/// A passage becomes a wall if at least this number of the eight surrounding cells are walls.
+const BIRTH_LIMIT: usize = 5;
+
+/// A wall stays a wall if at least this number of the eight surrounding cells are walls.
+const SURVIVAL_LIMIT: usize = 4;
+
+/// Number of attempts to generate a cave large enough to place the start and the goal.
+const MAX_ATTEMPTS: usize = 100;
+
+/// Counts the walls among the eight cells surrounding the cell, where the outside of the maze counts as walls.
+fn count_surrounding_walls(cells: &Array2<MazeCellType>, coord: (usize, usize)) -> usize {
+    let (rows, cols) = cells.dim();
+    let (row, col) = coord;
+
+    iproduct!(0..3, 0..3)
+        .filter(|&(diff_row, diff_col)| (diff_row, diff_col) != (1, 1))
+        .filter(|&(diff_row, diff_col)| {
+            let adj_row = (row + diff_row).wrapping_sub(1);
+            let adj_col = (col + diff_col).wrapping_sub(1);
+
+            adj_row >= rows || adj_col >= cols || cells[(adj_row, adj_col)] == MazeCellType::Wall
+        })
+        .count()
+}
+
+/// Generator of a cave using a cellular automaton.
+///
+/// The cells are filled with walls at random, and smoothed by the rules
+/// that a cell becomes or stays a wall when surrounded by enough walls.
+/// Then only the largest connected region of passages is kept.
+/// Unlike the other generators, the result is open areas rather than one-cell-wide passages.
+pub(crate) struct CaveGenerator;
+
+impl PartialMazeGenerator for CaveGenerator {
+    fn generate(&self, shape: MazeShape, canvas: &mut GenerationCanvas, rng: &mut dyn RngCore) {
+        let MazeShape { rows, cols } = shape;
+
+        for _ in 0..MAX_ATTEMPTS {
+            let mut cells = Array2::from_shape_simple_fn((rows, cols), || {
+                if rng.gen_bool(INIT_WALL_PROBABILITY) {
+                    MazeCellType::Wall
+                } else {
+                    MazeCellType::Passage
+                }
+            });
+
+            // The cells after each step, kept to be drawn only if the attempt succeeds.
+            let mut stages = vec![cells.clone()];
+
+            for _ in 0..NUM_SMOOTHING_STEPS {
+                cells = Array2::from_shape_fn((rows, cols), |coord| {
+                    let num_walls = count_surrounding_walls(&cells, coord);
+                    let limit = match cells[coord] {
+                        MazeCellType::Wall => SURVIVAL_LIMIT,
+                        _ => BIRTH_LIMIT,
+                    };
+
+                    if num_walls >= limit {
+                        MazeCellType::Wall
+                    } else {
+                        MazeCellType::Passage
+                    }
+                });
+                stages.push(cells.clone());
+            }
+
+            // Retry in the rare case that the cave is too small to place the start and the goal.
+            if keep_largest_region(&mut cells, shape) >= 2 {
+                stages.push(cells);
+                for stage in &stages {
+                    canvas.overwrite(stage);
+                }
+
+                return;
+            }
+        }
+
+        // A maze too small for a cave is left open.
+        canvas.fill(MazeCellType::Passage);
+    }
+
+    fn follows_lattice(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+    use crate::maze::generate_maze::{generate_maze, GenerationConfig};
+
+    #[test]
+    fn test_cave_is_connected() {
+        let config = GenerationConfig {
+            algorithm: "Cave".to_string(),
+            ..Default::default()
+        };
+
+        for seed in 0..20 {
+            let maze = generate_maze(
+                MazeShape::new(21, 31),
+                &config,
+                &mut ChaCha8Rng::seed_from_u64(seed),
+            );
+            assert!(maze.cells[maze.start].is_passable());
+            assert!(maze.cells[maze.goal].is_passable());
+
+            // The cave is connected if keeping its largest region keeps all of its passable cells.
+            let mut cells = maze.cells.mapv(|cell_type| {
+                if cell_type.is_passable() {
+                    MazeCellType::Passage
+                } else {
+                    MazeCellType::Wall
+                }
+            });
+            let num_passable = cells.iter().filter(|cell| cell.is_passable()).count();
+            assert_eq!(keep_largest_region(&mut cells, maze.shape), num_passable);
+        }
+    }
+}
@@ -0,0 +1,187 @@
+use rand::prelude::*;
+
+use crate::maze::{MazeCellType, MazeShape};
+
+use super::{GenerationCanvas, PartialMazeGenerator};
+
+/// An area is not split if either half would be narrower than this.
+const MIN_AREA_SIZE: usize = 7;
+
+/// Minimum height and width of a room, unless the area is too small.
+const MIN_ROOM_SIZE: usize = 3;
+
+/// Rectangle given by the coordinates of its top-left and bottom-right cells.
+#[derive(Debug, Clone, Copy)]
+struct Rect {
+    top_left: (usize, usize),
+    bottom_right: (usize, usize),
+}
+
+impl Rect {
+    fn height(&self) -> usize {
+        self.bottom_right.0 - self.top_left.0 + 1
+    }
+
+    fn width(&self) -> usize {
+        self.bottom_right.1 - self.top_left.1 + 1
+    }
+
+    /// Returns a random cell in the rectangle.
+    fn random_cell(&self, rng: &mut dyn RngCore) -> (usize, usize) {
+        (
+            rng.gen_range(self.top_left.0..=self.bottom_right.0),
+            rng.gen_range(self.top_left.1..=self.bottom_right.1),
+        )
+    }
+}
+
+/// Returns the start and the length of a random room along one side of an area,
+/// leaving a margin of one cell on both ends if possible.
+fn random_room_span(area_start: usize, area_len: usize, rng: &mut dyn RngCore) -> (usize, usize) {
+    let (inner_start, inner_len) = if area_len >= 3 {
+        (area_start + 1, area_len - 2)
+    } else {
+        (area_start, area_len)
+    };
+
+    let room_len = rng.gen_range(MIN_ROOM_SIZE.min(inner_len)..=inner_len);
+    let room_start = inner_start + rng.gen_range(0..=inner_len - room_len);
+
+    (room_start, room_len)
+}
+
+/// Digs a corridor bending once between the two cells.
+fn dig_corridor(
+    cells: &mut GenerationCanvas,
+    from: (usize, usize),
+    to: (usize, usize),
+    rng: &mut dyn RngCore,
+) {
+    let corner = if rng.gen_bool(0.5) {
+        (from.0, to.1)
+    } else {
+        (to.0, from.1)
+    };
+
+    for (start, end) in [(from, corner), (corner, to)] {
+        for row in start.0.min(end.0)..=start.0.max(end.0) {
+            for col in start.1.min(end.1)..=start.1.max(end.1) {
+                cells.set((row, col), MazeCellType::Passage);
+            }
+        }
+    }
+}
+
+/// Splits the area recursively, places a room in each final area, and connects the rooms.
+/// Returns the rooms placed in the area.
+fn split_area(cells: &mut GenerationCanvas, area: Rect, rng: &mut dyn RngCore) -> Vec<Rect> {
+    let Rect {
+        top_left: (top, left),
+        bottom_right: (bottom, right),
+    } = area;
+
+    let can_split_rows = area.height() >= 2 * MIN_AREA_SIZE;
+    let can_split_cols = area.width() >= 2 * MIN_AREA_SIZE;
+
+    // Split across the longer side so that the areas do not become long and thin.
+    let split_rows = match (can_split_rows, can_split_cols) {
+        (false, false) => None,
+        (true, false) => Some(true),
+        (false, true) => Some(false),
+        (true, true) => Some(match area.height().cmp(&area.width()) {
+            std::cmp::Ordering::Greater => true,
+            std::cmp::Ordering::Less => false,
+            std::cmp::Ordering::Equal => rng.gen_bool(0.5),
+        }),
+    };
+
+    let Some(split_rows) = split_rows else {
+        // Place a room in the area.
+        let (room_top, room_height) = random_room_span(top, area.height(), rng);
+        let (room_left, room_width) = random_room_span(left, area.width(), rng);
+
+        let room = Rect {
+            top_left: (room_top, room_left),
+            bottom_right: (room_top + room_height - 1, room_left + room_width - 1),
+        };
+        for row in room_top..room_top + room_height {
+            for col in room_left..room_left + room_width {
+                cells.set((row, col), MazeCellType::Passage);
+            }
+        }
+
+        return vec![room];
+    };
+
+    let (area1, area2) = if split_rows {
+        let split_row = rng.gen_range(top + MIN_AREA_SIZE..=bottom + 1 - MIN_AREA_SIZE);
+        (
+            Rect {
+                top_left: (top, left),
+                bottom_right: (split_row - 1, right),
+            },
+            Rect {
+                top_left: (split_row, left),
+                bottom_right: (bottom, right),
+            },
+        )
+    } else {
+        let split_col = rng.gen_range(left + MIN_AREA_SIZE..=right + 1 - MIN_AREA_SIZE);
+        (
+            Rect {
+                top_left: (top, left),
+                bottom_right: (bottom, split_col - 1),
+            },
+            Rect {
+                top_left: (top, split_col),
+                bottom_right: (bottom, right),
+            },
+        )
+    };
+
+    let mut rooms1 = split_area(cells, area1, rng);
+    let rooms2 = split_area(cells, area2, rng);
+
+    // Connect a room of each half, which keeps all the rooms connected.
+    let &room1 = rooms1.choose(rng).unwrap();
+    let &room2 = rooms2.choose(rng).unwrap();
+    let from = room1.random_cell(rng);
+    let to = room2.random_cell(rng);
+    dig_corridor(cells, from, to, rng);
+
+    rooms1.extend(rooms2);
+    rooms1
+}
+
+/// Generator of a dungeon of rooms connected by corridors using binary space partitioning.
+///
+/// The maze is split in two recursively, a room of random size is placed in each final area,
+/// and the rooms of the two halves of each split are connected by a corridor bending once.
+/// The corridors enter the rooms through openings in their walls, which work as doors.
+pub(crate) struct DungeonGenerator;
+
+impl PartialMazeGenerator for DungeonGenerator {
+    fn generate(&self, shape: MazeShape, cells: &mut GenerationCanvas, rng: &mut dyn RngCore) {
+        let MazeShape { rows, cols } = shape;
+
+        let whole_area = Rect {
+            top_left: (0, 0),
+            bottom_right: (rows - 1, cols - 1),
+        };
+        split_area(cells, whole_area, rng);
+
+        // A maze too small for a room of two cells is left open.
+        if cells
+            .iter()
+            .filter(|cell_type| cell_type.is_passable())
+            .count()
+            < 2
+        {
+            cells.fill(MazeCellType::Passage);
+        }
+    }
+
+    fn follows_lattice(&self) -> bool {
+        false
+    }
+}
@@ -0,0 +1,81 @@
+use std::collections::BTreeMap;
+
+use rand::prelude::*;
+
+use crate::maze::{MazeCellType, MazeShape};
+
+use super::{GenerationCanvas, PartialMazeGenerator};
+
+/// Generator of a maze consisting of only passages and walls using Eller's algorithm.
+///
+/// The maze is generated one row at a time, keeping track of which cells of the row are connected.
+/// Adjacent cells not yet connected are joined at random,
+/// and each connected set is extended down to the next row at one or more random cells.
+/// In the last row, all remaining sets are joined.
+pub(crate) struct EllerGenerator;
+
+impl PartialMazeGenerator for EllerGenerator {
+    fn generate(&self, shape: MazeShape, cells: &mut GenerationCanvas, rng: &mut dyn RngCore) {
+        let MazeShape { rows, cols } = shape;
+
+        // Identifiers of the connected sets of the cells in the current row, indexed by the column.
+        let mut set_ids = (0..cols.div_ceil(2)).collect::<Vec<_>>();
+        let mut next_set_id = set_ids.len();
+
+        for row in (0..rows).step_by(2) {
+            let is_last_row = row + 1 >= rows;
+
+            for col in (0..cols).step_by(2) {
+                cells.set((row, col), MazeCellType::Passage);
+            }
+
+            // Join adjacent cells in different sets.
+            for idx in 0..set_ids.len() - 1 {
+                let (left_id, right_id) = (set_ids[idx], set_ids[idx + 1]);
+
+                if left_id != right_id && (is_last_row || rng.gen_bool(0.5)) {
+                    cells.set((row, 2 * idx + 1), MazeCellType::Passage);
+
+                    for set_id in &mut set_ids {
+                        if *set_id == right_id {
+                            *set_id = left_id;
+                        }
+                    }
+                }
+            }
+
+            if is_last_row {
+                break;
+            }
+
+            // Extend each set down at least once.
+            let mut set_members: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+            for (idx, &set_id) in set_ids.iter().enumerate() {
+                set_members.entry(set_id).or_default().push(idx);
+            }
+
+            let mut next_set_ids = vec![None; set_ids.len()];
+            for (set_id, members) in set_members {
+                let &first_down = members.choose(rng).unwrap();
+
+                for idx in members {
+                    if idx == first_down || rng.gen_bool(0.5) {
+                        cells.set((row + 1, 2 * idx), MazeCellType::Passage);
+                        next_set_ids[idx] = Some(set_id);
+                    }
+                }
+            }
+
+            // Cells not connected from above start new sets.
+            set_ids = next_set_ids
+                .into_iter()
+                .map(|set_id| {
+                    set_id.unwrap_or_else(|| {
+                        next_set_id += 1;
+                        next_set_id - 1
+                    })
+                })
+                .collect();
+        }
+    }
+}
@@ -0,0 +1,400 @@
+use itertools::{iproduct, Itertools};
+use ndarray::prelude::*;
+use rand::prelude::*;
+
+use crate::maze::edge_walls::EdgeWalls;
+use crate::maze::obstacle::place_patrols;
+use crate::maze::{calculate_manhattan_distance, MazeCellType, MazeGrid, MazeShape};
+
+use super::PartialMazeGenerator;
+
+/// Number of random candidates tried when looking for the best start and goal.
+const MAX_CANDIDATE_ENDPOINTS: usize = 10;
+
+/// Strategy of placing the start and the goal on a generated maze.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum EndpointStrategy {
+    /// The placement chosen by the generator if any,
+    /// otherwise the farthest apart of random pairs of dead ends for a maze following the lattice,
+    /// and the ends of a diameter for the other mazes.
+    Auto,
+
+    /// The two cells farthest apart, found by going to the farthest cell twice from a random cell.
+    Diameter,
+
+    /// The cells closest to the top-left and bottom-right corners.
+    Corners,
+
+    /// Two random cells.
+    Random,
+
+    /// A pair of cells as close as possible to the given distance apart.
+    Distance { distance: usize },
+
+    /// The given cells if they are passable in the maze, otherwise the automatic placement.
+    Chosen {
+        start: (usize, usize),
+        goal: (usize, usize),
+    },
+}
+
+impl EndpointStrategy {
+    /// Creates the strategy of the given name.
+    /// The distance is used only by the distance strategy, and the chosen cells only by the manual strategy.
+    pub(crate) fn new(
+        name: &str,
+        distance: usize,
+        chosen: ((usize, usize), (usize, usize)),
+    ) -> Self {
+        match name {
+            "Auto" => Self::Auto,
+            "Diameter" => Self::Diameter,
+            "Corners" => Self::Corners,
+            "Random" => Self::Random,
+            "Distance" => Self::Distance {
+                distance: distance.max(1),
+            },
+            "Manual" => Self::Chosen {
+                start: chosen.0,
+                goal: chosen.1,
+            },
+            name => panic!("{} is the unknown endpoint strategy.", name),
+        }
+    }
+
+    /// Returns the same strategy for the lattice on which a maze with thin walls is generated,
+    /// where the rooms are two cells apart.
+    pub(crate) fn on_lattice(self) -> Self {
+        match self {
+            Self::Distance { distance } => Self::Distance {
+                distance: 2 * distance,
+            },
+            Self::Chosen { start, goal } => Self::Chosen {
+                start: EdgeWalls::to_lattice(start),
+                goal: EdgeWalls::to_lattice(goal),
+            },
+            strategy => strategy,
+        }
+    }
+}
+
+/// Returns whether the start or the goal may be placed on the cell.
+///
+/// In a maze following the lattice, only the cells at even coordinates are considered,
+/// so that the endpoints are not placed between two cells.
+fn is_candidate(
+    cells: &Array2<MazeCellType>,
+    coord: (usize, usize),
+    follows_lattice: bool,
+) -> bool {
+    let (row, col) = coord;
+    cells[coord].is_passable() && (!follows_lattice || (row % 2 == 0 && col % 2 == 0))
+}
+
+/// Randomly chooses which of the pair is the start and which is the goal.
+fn orient_randomly(
+    endpoints: ((usize, usize), (usize, usize)),
+    rng: &mut dyn RngCore,
+) -> ((usize, usize), (usize, usize)) {
+    if rng.gen_bool(0.5) {
+        endpoints
+    } else {
+        (endpoints.1, endpoints.0)
+    }
+}
+
+/// Returns two distinct random cells from the candidates.
+fn choose_random_endpoints(
+    candidates: &[(usize, usize)],
+    rng: &mut dyn RngCore,
+) -> ((usize, usize), (usize, usize)) {
+    let (&end1, &end2) = candidates
+        .choose_multiple(rng, 2)
+        .collect_tuple()
+        .expect("The maze must contain multiple candidates for the start and the goal.");
+
+    (end1, end2)
+}
+
+/// Returns a pair of cells far apart, found by going to the farthest cell twice from a random cell.
+///
+/// The pair is the farthest apart in a maze without loops, and nearly so otherwise.
+fn find_farthest_endpoints(
+    maze_shape: MazeShape,
+    maze: &Array2<MazeCellType>,
+    candidates: &[(usize, usize)],
+    rng: &mut dyn RngCore,
+) -> ((usize, usize), (usize, usize)) {
+    let find_farthest = |origin| {
+        let dist_grid = maze_shape.dist_grid_from(origin, |_, coord| maze[coord].is_passable());
+        candidates
+            .iter()
+            .filter_map(|&coord| Some((coord, dist_grid[coord]?)))
+            .max_by_key(|&(_, dist)| dist)
+            .map(|(coord, _)| coord)
+            .unwrap()
+    };
+
+    let &origin = candidates.choose(rng).unwrap();
+    let end1 = find_farthest(origin);
+    let end2 = find_farthest(end1);
+
+    // The random cell may be cut off from all the other cells.
+    if end1 == end2 {
+        return choose_random_endpoints(candidates, rng);
+    }
+
+    orient_randomly((end1, end2), rng)
+}
+
+/// Returns the farthest apart of random pairs of dead ends.
+/// If there are not enough dead ends, any cells at even coordinates are used instead.
+fn select_dead_end_endpoints(
+    maze_shape: MazeShape,
+    maze: &Array2<MazeCellType>,
+    rng: &mut dyn RngCore,
+) -> ((usize, usize), (usize, usize)) {
+    let MazeShape { rows, cols } = maze_shape;
+
+    let rooms = iproduct!((0..rows).step_by(2), (0..cols).step_by(2)).collect_vec();
+    let mut dead_ends = rooms
+        .iter()
+        .copied()
+        .filter(|&coord| {
+            maze_shape
+                .adjacent_coordinates(coord)
+                .filter(|&adj_coord| maze[adj_coord] == MazeCellType::Passage)
+                .count()
+                == 1
+        })
+        .collect_vec();
+
+    // A braided maze may have no dead ends.
+    if dead_ends.len() < 2 {
+        dead_ends = rooms;
+    }
+
+    // Pairs are sampled one by one, since listing all pairs takes quadratic time on a large maze.
+    let endpoints = (0..MAX_CANDIDATE_ENDPOINTS)
+        .map(|_| choose_random_endpoints(&dead_ends, rng))
+        .max_by_key(|&(end1, end2)| {
+            maze_shape.dist_grid_from(end1, |_, coord| maze[coord].is_passable())[end2].unwrap()
+        })
+        .unwrap();
+
+    orient_randomly(endpoints, rng)
+}
+
+/// Returns the candidates closest to the top-left and the bottom-right corners.
+fn find_corner_endpoints(
+    maze_shape: MazeShape,
+    candidates: &[(usize, usize)],
+    rng: &mut dyn RngCore,
+) -> ((usize, usize), (usize, usize)) {
+    let closest_to = |corner: (usize, usize), excluded: Option<(usize, usize)>| {
+        candidates
+            .iter()
+            .copied()
+            .filter(|&coord| Some(coord) != excluded)
+            .min_by_key(|&coord| calculate_manhattan_distance(corner, coord))
+            .unwrap()
+    };
+
+    let end1 = closest_to((0, 0), None);
+    let end2 = closest_to((maze_shape.rows - 1, maze_shape.cols - 1), Some(end1));
+
+    orient_randomly((end1, end2), rng)
+}
+
+/// Returns a pair of cells whose distance is the closest to `distance`,
+/// among the cells reachable from a few random starts.
+fn find_endpoints_at_distance(
+    maze_shape: MazeShape,
+    maze: &Array2<MazeCellType>,
+    candidates: &[(usize, usize)],
+    distance: usize,
+    rng: &mut dyn RngCore,
+) -> ((usize, usize), (usize, usize)) {
+    let mut best_endpoints = None;
+    let mut best_diff = usize::MAX;
+
+    for _ in 0..MAX_CANDIDATE_ENDPOINTS {
+        let &start = candidates.choose(rng).unwrap();
+        let dist_grid = maze_shape.dist_grid_from(start, |_, coord| maze[coord].is_passable());
+
+        let goals = candidates
+            .iter()
+            .filter(|&&coord| coord != start)
+            .filter_map(|&coord| Some((coord, dist_grid[coord]?.abs_diff(distance))))
+            .min_set_by_key(|&(_, diff)| diff);
+
+        if let Some(&(goal, diff)) = goals.choose(rng) {
+            if diff < best_diff {
+                best_endpoints = Some((start, goal));
+                best_diff = diff;
+            }
+        }
+
+        if best_diff == 0 {
+            break;
+        }
+    }
+
+    best_endpoints.unwrap_or_else(|| choose_random_endpoints(candidates, rng))
+}
+
+/// Chooses the start and the goal on the generated cells by the strategy.
+pub(super) fn place_endpoints(
+    strategy: EndpointStrategy,
+    generator: &dyn PartialMazeGenerator,
+    maze_shape: MazeShape,
+    maze: &Array2<MazeCellType>,
+    rng: &mut dyn RngCore,
+) -> ((usize, usize), (usize, usize)) {
+    let follows_lattice = generator.follows_lattice();
+    let candidates = maze
+        .indexed_iter()
+        .map(|(coord, _)| coord)
+        .filter(|&coord| is_candidate(maze, coord, follows_lattice))
+        .collect_vec();
+
+    match strategy {
+        EndpointStrategy::Auto => match generator.choose_endpoints(maze, rng) {
+            Some(endpoints) => endpoints,
+            None if follows_lattice => select_dead_end_endpoints(maze_shape, maze, rng),
+            None => find_farthest_endpoints(maze_shape, maze, &candidates, rng),
+        },
+        EndpointStrategy::Diameter => find_farthest_endpoints(maze_shape, maze, &candidates, rng),
+        EndpointStrategy::Corners => find_corner_endpoints(maze_shape, &candidates, rng),
+        EndpointStrategy::Random => choose_random_endpoints(&candidates, rng),
+        EndpointStrategy::Distance { distance } => {
+            find_endpoints_at_distance(maze_shape, maze, &candidates, distance, rng)
+        }
+        EndpointStrategy::Chosen { start, goal } => {
+            let is_valid =
+                |coord| maze_shape.in_range(coord) && is_candidate(maze, coord, follows_lattice);
+
+            if start != goal && is_valid(start) && is_valid(goal) {
+                (start, goal)
+            } else {
+                place_endpoints(EndpointStrategy::Auto, generator, maze_shape, maze, rng)
+            }
+        }
+    }
+}
+
+/// Returns the maze with the start and the goal moved to the given cells,
+/// and the guards, if any, placed again to keep clear of the new path.
+/// Returns `None` if either cell could not be chosen by the generator of the maze or both cells are the same.
+pub(crate) fn move_endpoints<R>(
+    maze: &MazeGrid,
+    start: (usize, usize),
+    goal: (usize, usize),
+    rng: &mut R,
+) -> Option<MazeGrid>
+where
+    R: Rng,
+{
+    let is_valid = |coord| {
+        maze.shape.in_range(coord) && is_candidate(&maze.cells, coord, maze.follows_lattice)
+    };
+    if start == goal || !is_valid(start) || !is_valid(goal) {
+        return None;
+    }
+
+    let mut moved_maze = maze.clone();
+    moved_maze.cells[maze.start] = MazeCellType::Passage;
+    moved_maze.cells[maze.goal] = MazeCellType::Passage;
+    moved_maze.cells[start] = MazeCellType::Start;
+    moved_maze.cells[goal] = MazeCellType::Goal;
+    moved_maze.start = start;
+    moved_maze.goal = goal;
+    if !maze.obstacles.is_empty() {
+        moved_maze.obstacles = place_patrols(&moved_maze, rng);
+    }
+
+    Some(moved_maze)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand_chacha::ChaCha8Rng;
+
+    use super::super::{generate_maze, GenerationConfig};
+    use super::*;
+
+    #[test]
+    fn test_place_endpoints() {
+        const MAZE_ROWS: usize = 31;
+        const MAZE_COLS: usize = 41;
+        const DISTANCE: usize = 20;
+
+        let mut rng = ChaCha8Rng::seed_from_u64(12345);
+        let shape = MazeShape::new(MAZE_ROWS, MAZE_COLS);
+
+        for algorithm in ["Backtracker", "Kruskal", "Cave", "Obstacle field"] {
+            for strategy in [
+                EndpointStrategy::Auto,
+                EndpointStrategy::Diameter,
+                EndpointStrategy::Corners,
+                EndpointStrategy::Random,
+                EndpointStrategy::Distance { distance: DISTANCE },
+            ] {
+                let config = GenerationConfig {
+                    algorithm: algorithm.to_string(),
+                    endpoint_strategy: strategy,
+                    ..Default::default()
+                };
+                let maze = generate_maze(shape, &config, &mut rng);
+
+                assert_ne!(maze.start, maze.goal);
+                assert_eq!(maze.cells[maze.start], MazeCellType::Start);
+                assert_eq!(maze.cells[maze.goal], MazeCellType::Goal);
+
+                // A perfect maze has cells at any moderate distance from most cells.
+                if let ("Backtracker", EndpointStrategy::Distance { distance }) =
+                    (algorithm, strategy)
+                {
+                    let dist = maze.dist_grid_from(maze.start)[maze.goal];
+                    assert_eq!(dist, Some(distance));
+                }
+            }
+        }
+
+        // The chosen cells are used if they are passable, and ignored otherwise.
+        let chosen = EndpointStrategy::Chosen {
+            start: (0, 0),
+            goal: (MAZE_ROWS - 1, MAZE_COLS - 1),
+        };
+        let config = GenerationConfig {
+            endpoint_strategy: chosen,
+            ..Default::default()
+        };
+        let maze = generate_maze(shape, &config, &mut rng);
+        assert_eq!(
+            (maze.start, maze.goal),
+            ((0, 0), (MAZE_ROWS - 1, MAZE_COLS - 1))
+        );
+
+        // In a perfect maze, the cells at even coordinates are passable and those at odd ones are walls.
+        assert!(maze.follows_lattice);
+        let moved_maze = move_endpoints(&maze, (0, 2), maze.goal, &mut rng).unwrap();
+        assert_eq!(moved_maze.cells[(0, 0)], MazeCellType::Passage);
+        assert_eq!(moved_maze.cells[(0, 2)], MazeCellType::Start);
+        assert!(move_endpoints(&maze, (1, 1), maze.goal, &mut rng).is_none());
+
+        // The passages between the cells at even coordinates are not chosen either, unlike in a maze not on the lattice.
+        let gap = maze
+            .cells
+            .indexed_iter()
+            .find(|&((row, col), cell)| cell.is_passable() && (row % 2 == 1 || col % 2 == 1))
+            .map(|(coord, _)| coord)
+            .unwrap();
+        assert!(move_endpoints(&maze, gap, maze.goal, &mut rng).is_none());
+        let off_lattice_maze = MazeGrid {
+            follows_lattice: false,
+            ..maze.clone()
+        };
+        assert!(move_endpoints(&off_lattice_maze, gap, maze.goal, &mut rng).is_some());
+    }
+}
@@ -0,0 +1,89 @@
+use rand::prelude::*;
+
+use crate::maze::{MazeCellType, MazeShape};
+
+use super::{neighboring_cells, random_cell, GenerationCanvas, PartialMazeGenerator};
+
+/// Policy of choosing the cell to grow the maze from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum CellChoicePolicy {
+    /// The most recently added cell, giving long winding passages like the backtracker.
+    Newest,
+
+    /// The earliest added cell, giving long straight passages from the first cell.
+    Oldest,
+
+    /// A random cell, giving many short dead ends like Prim's algorithm.
+    Random,
+
+    /// The newest cell with the given probability, otherwise a random cell.
+    Mix { newest_ratio: f64 },
+}
+
+impl CellChoicePolicy {
+    /// Creates the policy of the given name.
+    /// The ratio is used only by the mixed policy.
+    pub(crate) fn new(name: &str, newest_ratio: f64) -> Self {
+        match name {
+            "Newest" => Self::Newest,
+            "Oldest" => Self::Oldest,
+            "Random" => Self::Random,
+            "Mix" => Self::Mix {
+                newest_ratio: newest_ratio.clamp(0.0, 1.0),
+            },
+            name => panic!("{} is the unknown cell choice policy.", name),
+        }
+    }
+
+    /// Chooses the index of a cell from `len` cells in order of addition.
+    fn choose(&self, len: usize, rng: &mut dyn RngCore) -> usize {
+        match *self {
+            Self::Newest => len - 1,
+            Self::Oldest => 0,
+            Self::Random => rng.gen_range(0..len),
+            Self::Mix { newest_ratio } => {
+                if rng.gen_bool(newest_ratio) {
+                    len - 1
+                } else {
+                    rng.gen_range(0..len)
+                }
+            }
+        }
+    }
+}
+
+/// Generator of a maze consisting of only passages and walls using the growing tree algorithm.
+///
+/// The maze grows from a list of cells that may still have walls to dig through.
+/// A cell is chosen from the list by the policy and connected to a random neighboring cell not yet in the maze,
+/// which is added to the list, and the cell is removed from the list when it has no such neighbor.
+pub(crate) struct GrowingTreeGenerator {
+    pub(crate) policy: CellChoicePolicy,
+}
+
+impl PartialMazeGenerator for GrowingTreeGenerator {
+    fn generate(&self, shape: MazeShape, cells: &mut GenerationCanvas, rng: &mut dyn RngCore) {
+        let init_coord = random_cell(shape, rng);
+        cells.set(init_coord, MazeCellType::Passage);
+        let mut active_coords = vec![init_coord];
+
+        while !active_coords.is_empty() {
+            let idx = self.policy.choose(active_coords.len(), rng);
+
+            let next_step = neighboring_cells(shape, active_coords[idx])
+                .filter(|&(next_coord, _)| cells[next_coord] == MazeCellType::Wall)
+                .choose(rng);
+
+            match next_step {
+                Some((next_coord, wall_coord)) => {
+                    cells.set(wall_coord, MazeCellType::Passage);
+                    cells.set(next_coord, MazeCellType::Passage);
+                    active_coords.push(next_coord);
+                }
+                None => {
+                    active_coords.remove(idx);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use itertools::iproduct;
+use rand::prelude::*;
+
+use crate::maze::{MazeCellType, MazeShape};
+
+use super::{neighboring_cells, random_cell, GenerationCanvas, PartialMazeGenerator};
+
+/// Generator of a maze consisting of only passages and walls using the hunt-and-kill algorithm.
+///
+/// A random walk digs through cells not yet in the maze until it gets stuck.
+/// Then the rows are scanned from the top for a cell not in the maze next to one in the maze,
+/// which is connected to the maze and starts the next walk.
+pub(crate) struct HuntAndKillGenerator;
+
+impl PartialMazeGenerator for HuntAndKillGenerator {
+    fn generate(&self, shape: MazeShape, cells: &mut GenerationCanvas, rng: &mut dyn RngCore) {
+        let MazeShape { rows, cols } = shape;
+
+        let mut coord = random_cell(shape, rng);
+        cells.set(coord, MazeCellType::Passage);
+
+        loop {
+            // Walk.
+            let next_step = neighboring_cells(shape, coord)
+                .filter(|&(next_coord, _)| cells[next_coord] == MazeCellType::Wall)
+                .choose(rng);
+
+            if let Some((next_coord, wall_coord)) = next_step {
+                cells.set(wall_coord, MazeCellType::Passage);
+                cells.set(next_coord, MazeCellType::Passage);
+                coord = next_coord;
+                continue;
+            }
+
+            // Hunt.
+            let hunted =
+                iproduct!((0..rows).step_by(2), (0..cols).step_by(2)).find_map(|hunted_coord| {
+                    if cells[hunted_coord] != MazeCellType::Wall {
+                        return None;
+                    }
+
+                    neighboring_cells(shape, hunted_coord)
+                        .filter(|&(next_coord, _)| cells[next_coord] == MazeCellType::Passage)
+                        .choose(rng)
+                        .map(|(_, wall_coord)| (hunted_coord, wall_coord))
+                });
+
+            let Some((hunted_coord, wall_coord)) = hunted else {
+                break;
+            };
+
+            cells.set(wall_coord, MazeCellType::Passage);
+            cells.set(hunted_coord, MazeCellType::Passage);
+            coord = hunted_coord;
+        }
+    }
+}
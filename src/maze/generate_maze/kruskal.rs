@@ -0,0 +1,93 @@
+use itertools::iproduct;
+use rand::prelude::*;
+
+use crate::maze::{MazeCellType, MazeShape};
+
+use super::{GenerationCanvas, PartialMazeGenerator};
+
+/// Disjoint sets of cells connected by passages.
+struct UnionFind {
+    parents: Vec<usize>,
+    sizes: Vec<usize>,
+}
+
+impl UnionFind {
+    fn new(len: usize) -> Self {
+        Self {
+            parents: (0..len).collect(),
+            sizes: vec![1; len],
+        }
+    }
+
+    /// Returns the representative of the set containing `idx`.
+    fn find(&mut self, idx: usize) -> usize {
+        let mut root = idx;
+        while self.parents[root] != root {
+            root = self.parents[root];
+        }
+
+        // Compress the path to the root.
+        let mut idx = idx;
+        while self.parents[idx] != root {
+            idx = std::mem::replace(&mut self.parents[idx], root);
+        }
+
+        root
+    }
+
+    /// Merges the sets containing `idx1` and `idx2`, and returns whether they were different.
+    fn union(&mut self, idx1: usize, idx2: usize) -> bool {
+        let root1 = self.find(idx1);
+        let root2 = self.find(idx2);
+
+        if root1 == root2 {
+            return false;
+        }
+
+        let (larger, smaller) = if self.sizes[root1] >= self.sizes[root2] {
+            (root1, root2)
+        } else {
+            (root2, root1)
+        };
+        self.parents[smaller] = larger;
+        self.sizes[larger] += self.sizes[smaller];
+
+        true
+    }
+}
+
+/// Generator of a maze consisting of only passages and walls using randomized Kruskal's algorithm.
+///
+/// The walls between the cells at even coordinates are visited in random order,
+/// and each wall is dug through if the cells on both sides are not yet connected.
+/// The resulting maze has many short dead ends.
+pub(crate) struct KruskalGenerator;
+
+impl PartialMazeGenerator for KruskalGenerator {
+    fn generate(&self, shape: MazeShape, cells: &mut GenerationCanvas, rng: &mut dyn RngCore) {
+        let MazeShape { rows, cols } = shape;
+
+        for (row, col) in iproduct!((0..rows).step_by(2), (0..cols).step_by(2)) {
+            cells.set((row, col), MazeCellType::Passage);
+        }
+
+        // Walls between two cells at even coordinates lie at exactly one odd coordinate.
+        let mut walls = iproduct!(0..rows, 0..cols)
+            .filter(|&(row, col)| (row % 2 == 1) != (col % 2 == 1))
+            .collect::<Vec<_>>();
+        walls.shuffle(rng);
+
+        let mut cell_sets = UnionFind::new(rows * cols);
+        for (row, col) in walls {
+            let (coord1, coord2) = if row % 2 == 1 {
+                ((row - 1, col), (row + 1, col))
+            } else {
+                ((row, col - 1), (row, col + 1))
+            };
+
+            if cell_sets.union(shape.coord_to_idx(coord1), shape.coord_to_idx(coord2)) {
+                cells.set((row, col), MazeCellType::Passage);
+            }
+        }
+    }
+}
@@ -0,0 +1,803 @@
+use std::ops::Deref;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::{error, fmt};
+
+use itertools::iproduct;
+use ndarray::prelude::*;
+use rand::prelude::*;
+
+use super::analysis::{measure_difficulty, DifficultyTarget};
+use super::edge_walls::EdgeWalls;
+use super::obstacle::{place_patrols, MovingObstacles};
+use super::{MazeCellType, MazeGrid, MazeShape, ADJACENT_DISPLACEMENT};
+
+pub(crate) mod aldous_broder;
+pub(crate) mod backtracker;
+pub(crate) mod binary_tree;
+pub(crate) mod braid;
+pub(crate) mod cave;
+pub(crate) mod dungeon;
+pub(crate) mod eller;
+pub(crate) mod endpoints;
+pub(crate) mod growing_tree;
+pub(crate) mod hunt_and_kill;
+pub(crate) mod kruskal;
+pub(crate) mod obstacle_field;
+pub(crate) mod prim;
+pub(crate) mod recursive_division;
+pub(crate) mod sidewinder;
+pub(crate) mod terrain;
+pub(crate) mod wilson;
+
+use endpoints::EndpointStrategy;
+use growing_tree::CellChoicePolicy;
+
+/// Maximum number of mazes generated in search of one in the target difficulty.
+const MAX_DIFFICULTY_ATTEMPTS: usize = 100;
+
+/// Number of advances in which as many cells change as there are cells in the maze.
+const NUM_ANIMATION_STEPS: usize = 100;
+
+/// Settings of the maze generation.
+#[derive(Debug, Clone)]
+pub(crate) struct GenerationConfig {
+    /// Name of the generation algorithm.
+    pub(crate) algorithm: String,
+
+    /// Policy of the growing tree algorithm.
+    pub(crate) cell_choice_policy: CellChoicePolicy,
+
+    /// Fraction of the dead ends removed to create loops.
+    pub(crate) braid_factor: f64,
+
+    /// Probability of each cell being an obstacle in the obstacle field.
+    pub(crate) obstacle_density: f64,
+
+    /// Whether the obstacle field is guaranteed to have a path from the start to the goal.
+    pub(crate) guarantees_path: bool,
+
+    /// Strategy of placing the start and the goal.
+    pub(crate) endpoint_strategy: EndpointStrategy,
+
+    /// Whether guards patrolling the passages are placed.
+    pub(crate) places_guards: bool,
+
+    /// Whether the maze is made of rooms with walls on the edges between them,
+    /// which is possible only for a generator following the lattice.
+    pub(crate) thin_walls: bool,
+
+    /// Difficulty which the maze is regenerated until it falls in, if any.
+    pub(crate) difficulty_target: Option<DifficultyTarget>,
+}
+
+impl Default for GenerationConfig {
+    fn default() -> Self {
+        Self {
+            algorithm: "Backtracker".to_string(),
+            cell_choice_policy: CellChoicePolicy::Newest,
+            braid_factor: 0.0,
+            obstacle_density: 0.3,
+            guarantees_path: true,
+            endpoint_strategy: EndpointStrategy::Auto,
+            places_guards: false,
+            thin_walls: false,
+            difficulty_target: None,
+        }
+    }
+}
+
+/// Change of a cell to the given type during the generation.
+type CellChange = ((usize, usize), MazeCellType);
+
+/// Cells of a maze under generation, which sends every change of the cells in order
+/// so that the generation can be animated while it proceeds.
+///
+/// The cells are read through `Deref`, but only changed through the methods of the canvas.
+#[derive(Debug, Clone)]
+pub(crate) struct GenerationCanvas {
+    cells: Array2<MazeCellType>,
+
+    /// Sender of the changes of the cells, if the generation is animated.
+    change_sender: Option<mpsc::SyncSender<CellChange>>,
+}
+
+impl GenerationCanvas {
+    /// Creates a canvas filled with walls, which sends the changes of the cells to `change_sender` if given.
+    fn new(shape: MazeShape, change_sender: Option<mpsc::SyncSender<CellChange>>) -> Self {
+        Self {
+            cells: Array2::from_elem((shape.rows, shape.cols), MazeCellType::Wall),
+            change_sender,
+        }
+    }
+
+    /// Sets the type of the cell.
+    ///
+    /// The change is sent after the receiver takes the previous ones, so an animated generation waits for the animation.
+    /// If the receiver is gone, the generation goes on without sending.
+    pub(crate) fn set(&mut self, coord: (usize, usize), cell_type: MazeCellType) {
+        if self.cells[coord] != cell_type {
+            self.cells[coord] = cell_type;
+
+            if let Some(change_sender) = &self.change_sender {
+                let _ = change_sender.send((coord, cell_type));
+            }
+        }
+    }
+
+    /// Sets the type of all cells.
+    pub(crate) fn fill(&mut self, cell_type: MazeCellType) {
+        for (row, col) in iproduct!(0..self.cells.nrows(), 0..self.cells.ncols()) {
+            self.set((row, col), cell_type);
+        }
+    }
+
+    /// Replaces the cells with the given ones, in row-major order.
+    pub(crate) fn overwrite(&mut self, cells: &Array2<MazeCellType>) {
+        for (coord, &cell_type) in cells.indexed_iter() {
+            self.set(coord, cell_type);
+        }
+    }
+}
+
+impl Deref for GenerationCanvas {
+    type Target = Array2<MazeCellType>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.cells
+    }
+}
+
+/// Generator of a maze consisting of only passages and walls.
+pub(crate) trait PartialMazeGenerator {
+    /// Generates the cells of a maze by drawing on the canvas, which is filled with walls at first.
+    fn generate(&self, shape: MazeShape, cells: &mut GenerationCanvas, rng: &mut dyn RngCore);
+
+    /// Generates the cells of a maze, and returns the cost of entering each cell.
+    /// Unless overridden, every cell costs one.
+    fn generate_with_costs(
+        &self,
+        shape: MazeShape,
+        cells: &mut GenerationCanvas,
+        rng: &mut dyn RngCore,
+    ) -> Array2<usize> {
+        self.generate(shape, cells, rng);
+        Array2::ones((shape.rows, shape.cols))
+    }
+
+    /// Returns whether the passable cells are the cells at even coordinates and some of the walls between them.
+    ///
+    /// Only such mazes are braided and have the start and the goal placed at dead ends.
+    /// Otherwise, the start and the goal are placed as far apart as possible.
+    fn follows_lattice(&self) -> bool {
+        true
+    }
+
+    /// Chooses the start and the goal on the generated cells,
+    /// or returns `None` to leave them to the default placement.
+    fn choose_endpoints(
+        &self,
+        _cells: &Array2<MazeCellType>,
+        _rng: &mut dyn RngCore,
+    ) -> Option<((usize, usize), (usize, usize))> {
+        None
+    }
+}
+
+/// Names of all generation algorithms, in the order of the menu.
+pub(crate) const GENERATION_ALGORITHMS: [&str; 15] = [
+    "Backtracker",
+    "Kruskal",
+    "Prim",
+    "Wilson",
+    "Aldous-Broder",
+    "Recursive division",
+    "Eller",
+    "Binary tree",
+    "Sidewinder",
+    "Growing tree",
+    "Hunt-and-kill",
+    "Cave",
+    "Dungeon",
+    "Obstacle field",
+    "Terrain",
+];
+
+/// Creates the generator of the selected algorithm.
+pub(crate) fn create_generator(config: &GenerationConfig) -> Box<dyn PartialMazeGenerator> {
+    match config.algorithm.as_str() {
+        "Backtracker" => Box::new(backtracker::BacktrackerGenerator),
+        "Kruskal" => Box::new(kruskal::KruskalGenerator),
+        "Prim" => Box::new(prim::PrimGenerator),
+        "Wilson" => Box::new(wilson::WilsonGenerator),
+        "Aldous-Broder" => Box::new(aldous_broder::AldousBroderGenerator),
+        "Recursive division" => Box::new(recursive_division::RecursiveDivisionGenerator),
+        "Eller" => Box::new(eller::EllerGenerator),
+        "Binary tree" => Box::new(binary_tree::BinaryTreeGenerator),
+        "Sidewinder" => Box::new(sidewinder::SidewinderGenerator),
+        "Growing tree" => Box::new(growing_tree::GrowingTreeGenerator {
+            policy: config.cell_choice_policy,
+        }),
+        "Hunt-and-kill" => Box::new(hunt_and_kill::HuntAndKillGenerator),
+        "Cave" => Box::new(cave::CaveGenerator),
+        "Dungeon" => Box::new(dungeon::DungeonGenerator),
+        "Obstacle field" => Box::new(obstacle_field::ObstacleFieldGenerator {
+            density: config.obstacle_density,
+            guarantees_path: config.guarantees_path,
+        }),
+        "Terrain" => Box::new(terrain::TerrainGenerator),
+        algorithm => panic!("{} is the unknown generation algorithm.", algorithm),
+    }
+}
+
+/// Returns the cells at even coordinates two cells away from the cell, with the wall in between.
+fn neighboring_cells(
+    shape: MazeShape,
+    coord: (usize, usize),
+) -> impl Iterator<Item = ((usize, usize), (usize, usize))> {
+    let (row, col) = coord;
+
+    ADJACENT_DISPLACEMENT
+        .into_iter()
+        .map(move |(diff_row, diff_col)| {
+            let wall_coord = (row.wrapping_add(diff_row), col.wrapping_add(diff_col));
+            let next_coord = (
+                row.wrapping_add(diff_row.wrapping_mul(2)),
+                col.wrapping_add(diff_col.wrapping_mul(2)),
+            );
+
+            (next_coord, wall_coord)
+        })
+        .filter(move |&(next_coord, _)| shape.in_range(next_coord))
+}
+
+/// Returns a random cell at even coordinates.
+fn random_cell(shape: MazeShape, rng: &mut dyn RngCore) -> (usize, usize) {
+    (
+        2 * rng.gen_range(0..shape.rows.div_ceil(2)),
+        2 * rng.gen_range(0..shape.cols.div_ceil(2)),
+    )
+}
+
+/// Turns all passages into walls except the largest connected region of passages,
+/// and returns the number of cells in the region.
+fn keep_largest_region(cells: &mut Array2<MazeCellType>, shape: MazeShape) -> usize {
+    let is_passage = |coord: (usize, usize)| cells[coord] == MazeCellType::Passage;
+
+    let mut labeled = Array2::from_elem(cells.dim(), false);
+    let mut largest_region: Option<(usize, Array2<Option<usize>>)> = None;
+
+    for (coord, _) in cells.indexed_iter() {
+        if !is_passage(coord) || labeled[coord] {
+            continue;
+        }
+
+        // Label the region containing the cell.
+        let dist_grid = shape.dist_grid_from(coord, |_, coord| is_passage(coord));
+        let mut region_size = 0;
+        for (is_labeled, dist) in labeled.iter_mut().zip(&dist_grid) {
+            if dist.is_some() {
+                *is_labeled = true;
+                region_size += 1;
+            }
+        }
+
+        if largest_region
+            .as_ref()
+            .is_none_or(|&(largest_size, _)| region_size > largest_size)
+        {
+            largest_region = Some((region_size, dist_grid));
+        }
+    }
+
+    let Some((largest_size, dist_grid)) = largest_region else {
+        return 0;
+    };
+
+    for (cell_type, dist) in cells.iter_mut().zip(&dist_grid) {
+        if dist.is_none() {
+            *cell_type = MazeCellType::Wall;
+        }
+    }
+
+    largest_size
+}
+
+/// Generates a maze with the given settings.
+/// The maze consists of passages, walls, one starting point and one goal point,
+/// and is patrolled by guards if `places_guards` is set.
+pub(crate) fn generate_maze<R>(shape: MazeShape, config: &GenerationConfig, rng: &mut R) -> MazeGrid
+where
+    R: Rng + Clone,
+{
+    generate_maze_on_canvas(shape, config, rng, None, &AtomicBool::new(false))
+}
+
+/// Generates a maze with the given settings,
+/// sending the changes of the cells made during the generation in order to `change_sender` if given.
+///
+/// With a difficulty target, mazes are generated until one falls in it.
+/// If none does within `MAX_DIFFICULTY_ATTEMPTS`, or the search is stopped by setting `stopped`,
+/// the closest one so far is returned.
+/// Only the returned maze sends its changes, since it is generated again from the random state it was first generated from.
+fn generate_maze_on_canvas<R>(
+    shape: MazeShape,
+    config: &GenerationConfig,
+    rng: &mut R,
+    change_sender: Option<mpsc::SyncSender<CellChange>>,
+    stopped: &AtomicBool,
+) -> MazeGrid
+where
+    R: Rng + Clone,
+{
+    let Some(target) = &config.difficulty_target else {
+        return generate_single_maze_on_canvas(shape, config, rng, change_sender);
+    };
+
+    // A maze without a path from the start to the goal is the farthest from any target.
+    let calc_miss = |maze: &MazeGrid| {
+        measure_difficulty(maze).map_or(f64::INFINITY, |metrics| target.miss(&metrics))
+    };
+
+    let mut best_rng = rng.clone();
+    let mut best_miss = f64::INFINITY;
+
+    for _ in 0..MAX_DIFFICULTY_ATTEMPTS {
+        if stopped.load(Ordering::Relaxed) {
+            break;
+        }
+
+        let attempt_rng = rng.clone();
+        let miss = calc_miss(&generate_single_maze_on_canvas(shape, config, rng, None));
+        if miss < best_miss {
+            best_rng = attempt_rng;
+            best_miss = miss;
+        }
+
+        if best_miss == 0.0 {
+            break;
+        }
+    }
+
+    generate_single_maze_on_canvas(shape, config, &mut best_rng, change_sender)
+}
+
+/// Generates one maze with the given settings, ignoring the difficulty target,
+/// sending the changes of the cells made during the generation in order to `change_sender` if given.
+///
+/// A maze with thin walls is generated on the lattice of its rooms and the edges between them,
+/// so the changes are those of the cells of the lattice.
+fn generate_single_maze_on_canvas<R>(
+    shape: MazeShape,
+    config: &GenerationConfig,
+    rng: &mut R,
+    change_sender: Option<mpsc::SyncSender<CellChange>>,
+) -> MazeGrid
+where
+    R: Rng,
+{
+    let MazeShape { rows, cols } = shape;
+    let generator = create_generator(config);
+    let thin_walls = config.thin_walls && generator.follows_lattice();
+
+    assert!(
+        thin_walls || (rows % 2 == 1 && cols % 2 == 1),
+        "The number of rows and columns of the maze must be odd."
+    );
+
+    assert!(rows * cols >= 2, "The maze must contain multiple squares.");
+
+    let (canvas_shape, endpoint_strategy) = if thin_walls {
+        (
+            EdgeWalls::lattice_shape(shape),
+            config.endpoint_strategy.on_lattice(),
+        )
+    } else {
+        (shape, config.endpoint_strategy)
+    };
+
+    // Cells in the maze with undetermined start and goal points.
+    let mut cells = GenerationCanvas::new(canvas_shape, change_sender);
+    let costs = generator.generate_with_costs(canvas_shape, &mut cells, rng);
+
+    if generator.follows_lattice() {
+        braid::braid(&mut cells, canvas_shape, config.braid_factor, rng);
+    }
+
+    // Determine the start and goal points.
+    let (start, goal) = endpoints::place_endpoints(
+        endpoint_strategy,
+        generator.as_ref(),
+        canvas_shape,
+        &cells,
+        rng,
+    );
+    cells.set(start, MazeCellType::Start);
+    cells.set(goal, MazeCellType::Goal);
+
+    let mut maze = if thin_walls {
+        let (rooms, edge_walls) = EdgeWalls::split_lattice(&cells.cells);
+
+        MazeGrid {
+            cells: rooms,
+            start: EdgeWalls::from_lattice(start),
+            goal: EdgeWalls::from_lattice(goal),
+            shape,
+            costs: Array2::ones((rows, cols)),
+            obstacles: MovingObstacles::default(),
+            edge_walls: Some(edge_walls),
+            follows_lattice: false,
+        }
+    } else {
+        MazeGrid {
+            cells: cells.cells,
+            start,
+            goal,
+            shape,
+            costs,
+            obstacles: MovingObstacles::default(),
+            edge_walls: None,
+            follows_lattice: generator.follows_lattice(),
+        }
+    };
+
+    if config.places_guards {
+        maze.obstacles = place_patrols(&maze, rng);
+    }
+
+    maze
+}
+
+/// Error returned if the maze generation has already been finished, but an attempt is made to advance it.
+#[derive(Debug)]
+pub(crate) struct ExtraGenerationError;
+
+impl fmt::Display for ExtraGenerationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Maze generation has already been completed.")
+    }
+}
+
+impl error::Error for ExtraGenerationError {}
+
+/// Cell of the maze to be drawn again after it changed during the generation.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct GenerationRedraw {
+    pub(crate) cell_idx: usize,
+    pub(crate) cell_type: MazeCellType,
+
+    /// Whether a wall stands on each side of the cell, in the order of `ADJACENT_DISPLACEMENT`,
+    /// or `None` for a maze without thin walls.
+    pub(crate) walls: Option<[bool; 4]>,
+}
+
+/// Maze generation performed step by step to be visualized, starting from a maze filled with walls.
+pub(crate) trait MazeGenerator: 'static + Send {
+    /// Returns the shape of the maze being generated.
+    fn shape(&self) -> MazeShape;
+
+    /// Advance the maze generation by one step.
+    fn advance(&mut self) -> Result<Vec<GenerationRedraw>, ExtraGenerationError>;
+
+    /// Returns whether the generation is finished, so that no more cells change.
+    fn terminated(&self) -> bool;
+
+    /// Finishes the rest of the generation without animating it, and returns the generated maze.
+    ///
+    /// A search for a maze in the target difficulty is cut short, returning the closest maze found so far,
+    /// so that finishing does not keep the caller waiting for the remaining attempts.
+    fn finish(self: Box<Self>) -> MazeGrid;
+}
+
+/// Maze generation running on its own thread, which receives the changes of the cells as the generator makes them.
+///
+/// The generator waits whenever it is one advance ahead of the animation,
+/// so the maze is generated while it is animated instead of in advance.
+pub(crate) struct StreamedGeneration {
+    shape: MazeShape,
+
+    /// Cells of the lattice on which a maze with thin walls is generated, as changed so far.
+    lattice: Option<Array2<MazeCellType>>,
+
+    change_receiver: mpsc::Receiver<CellChange>,
+    handle: Option<thread::JoinHandle<MazeGrid>>,
+
+    /// Flag which stops the search for a maze in the target difficulty.
+    stopped: Arc<AtomicBool>,
+
+    maze: Option<MazeGrid>,
+    changes_per_advance: usize,
+}
+
+impl StreamedGeneration {
+    /// Starts generating a maze with the given settings.
+    pub(crate) fn new<R>(shape: MazeShape, config: GenerationConfig, mut rng: R) -> Self
+    where
+        R: Rng + Clone + Send + 'static,
+    {
+        let lattice =
+            (config.thin_walls && create_generator(&config).follows_lattice()).then(|| {
+                let MazeShape { rows, cols } = EdgeWalls::lattice_shape(shape);
+                Array2::from_elem((rows, cols), MazeCellType::Wall)
+            });
+        let canvas_area = lattice
+            .as_ref()
+            .map_or(shape.area(), |lattice| lattice.len());
+
+        let changes_per_advance = canvas_area.div_ceil(NUM_ANIMATION_STEPS);
+        let (change_sender, change_receiver) = mpsc::sync_channel(changes_per_advance);
+        let stopped = Arc::new(AtomicBool::new(false));
+        let handle = {
+            let stopped = Arc::clone(&stopped);
+            thread::spawn(move || {
+                generate_maze_on_canvas(shape, &config, &mut rng, Some(change_sender), &stopped)
+            })
+        };
+
+        Self {
+            shape,
+            lattice,
+            change_receiver,
+            handle: Some(handle),
+            stopped,
+            maze: None,
+            changes_per_advance,
+        }
+    }
+}
+
+impl MazeGenerator for StreamedGeneration {
+    fn shape(&self) -> MazeShape {
+        self.shape
+    }
+
+    /// Applies the changes made since the previous advance, up to a fixed number.
+    ///
+    /// While the generator is busy, such as searching for a maze in the target difficulty, no cells change.
+    /// For a maze with thin walls, a change of an edge of the lattice redraws the rooms on both sides of it.
+    fn advance(&mut self) -> Result<Vec<GenerationRedraw>, ExtraGenerationError> {
+        if self.terminated() {
+            return Err(ExtraGenerationError);
+        }
+
+        let mut redraws = vec![];
+        for _ in 0..self.changes_per_advance {
+            match self.change_receiver.try_recv() {
+                Ok((coord, cell_type)) => match &mut self.lattice {
+                    Some(lattice) => {
+                        lattice[coord] = cell_type;
+                        redraws.extend(EdgeWalls::cells_beside(coord).map(|room| {
+                            GenerationRedraw {
+                                cell_idx: self.shape.coord_to_idx(room),
+                                cell_type: lattice[EdgeWalls::to_lattice(room)],
+                                walls: Some(EdgeWalls::lattice_sides(lattice, room)),
+                            }
+                        }));
+                    }
+                    None => redraws.push(GenerationRedraw {
+                        cell_idx: self.shape.coord_to_idx(coord),
+                        cell_type,
+                        walls: None,
+                    }),
+                },
+                Err(mpsc::TryRecvError::Empty) => break,
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    // All changes have been sent, so the generator is about to return the maze.
+                    self.maze = self.handle.take().map(|handle| handle.join().unwrap());
+                    break;
+                }
+            }
+        }
+
+        Ok(redraws)
+    }
+
+    fn terminated(&self) -> bool {
+        self.maze.is_some()
+    }
+
+    fn finish(self: Box<Self>) -> MazeGrid {
+        let Self {
+            change_receiver,
+            handle,
+            stopped,
+            maze,
+            ..
+        } = *self;
+
+        // Without the receiver, the generator no longer waits for the animation.
+        drop(change_receiver);
+        stopped.store(true, Ordering::Relaxed);
+        maze.unwrap_or_else(|| handle.unwrap().join().unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_maze() {
+        const MAZE_ROWS: usize = 21;
+        const MAZE_COLS: usize = 21;
+
+        let mut rng = rand::thread_rng();
+
+        for algorithm in [
+            "Backtracker",
+            "Kruskal",
+            "Prim",
+            "Wilson",
+            "Aldous-Broder",
+            "Recursive division",
+            "Eller",
+            "Binary tree",
+            "Sidewinder",
+            "Growing tree",
+            "Hunt-and-kill",
+        ] {
+            let config = GenerationConfig {
+                algorithm: algorithm.to_string(),
+                cell_choice_policy: CellChoicePolicy::Mix { newest_ratio: 0.5 },
+                ..Default::default()
+            };
+            let maze = generate_maze(MazeShape::new(MAZE_ROWS, MAZE_COLS), &config, &mut rng);
+            println!("{}", maze);
+
+            // A perfect maze is a spanning tree of the cells at even coordinates.
+            let num_nodes = MAZE_ROWS.div_ceil(2) * MAZE_COLS.div_ceil(2);
+            let num_passable = maze.cells.iter().filter(|cell| cell.is_passable()).count();
+            assert_eq!(num_passable, 2 * num_nodes - 1);
+            assert!(maze.dist_grid_from(maze.start)[maze.goal].is_some());
+        }
+    }
+
+    #[test]
+    fn test_streamed_generation() {
+        use rand_chacha::ChaCha8Rng;
+
+        let shape = MazeShape::new(21, 21);
+
+        for algorithm in ["Backtracker", "Recursive division", "Cave", "Terrain"] {
+            let config = GenerationConfig {
+                algorithm: algorithm.to_string(),
+                ..Default::default()
+            };
+            let rng = ChaCha8Rng::seed_from_u64(12345);
+            let maze = generate_maze(shape, &config, &mut rng.clone());
+
+            // Applying all changes on a maze filled with walls gives the maze generated from the same seed.
+            let mut generation = StreamedGeneration::new(shape, config.clone(), rng.clone());
+            let mut cells = Array2::from_elem((shape.rows, shape.cols), MazeCellType::Wall);
+            while !generation.terminated() {
+                for redraw in generation.advance().unwrap() {
+                    cells[shape.idx_to_coord(redraw.cell_idx)] = redraw.cell_type;
+                }
+            }
+            assert_eq!(cells, maze.cells);
+            assert_eq!(Box::new(generation).finish().cells, maze.cells);
+
+            // Finishing the generation midway gives the same maze.
+            let mut generation = StreamedGeneration::new(shape, config, rng);
+            generation.advance().unwrap();
+            assert_eq!(Box::new(generation).finish().cells, maze.cells);
+        }
+    }
+
+    #[test]
+    fn test_thin_wall_maze() {
+        use crate::maze::searcher::{create_searcher, SearchProgress};
+        use rand_chacha::ChaCha8Rng;
+
+        // With thin walls, the maze may have an even number of rooms.
+        let shape = MazeShape::new(10, 12);
+        let config = GenerationConfig {
+            thin_walls: true,
+            ..Default::default()
+        };
+        let rng = ChaCha8Rng::seed_from_u64(12345);
+        let maze = generate_maze(shape, &config, &mut rng.clone());
+        let edge_walls = maze.edge_walls.as_ref().unwrap();
+
+        assert_eq!(maze.cells.dim(), (shape.rows, shape.cols));
+        assert!(maze.cells.iter().all(|cell| cell.is_passable()));
+
+        // A perfect maze is a spanning tree of the rooms, with one opening fewer than the rooms.
+        let num_openings: usize = iproduct!(0..shape.rows, 0..shape.cols)
+            .map(|coord| maze.passable_neighbors(coord).count())
+            .sum();
+        assert_eq!(num_openings / 2, shape.area() - 1);
+
+        // Applying all redraws on rooms walled in on all sides gives the generated rooms and walls.
+        let mut generation = StreamedGeneration::new(shape, config, rng);
+        let mut cells = Array2::from_elem((shape.rows, shape.cols), MazeCellType::Wall);
+        let mut walls = Array2::from_elem((shape.rows, shape.cols), [true; 4]);
+        while !generation.terminated() {
+            for redraw in generation.advance().unwrap() {
+                let coord = shape.idx_to_coord(redraw.cell_idx);
+                cells[coord] = redraw.cell_type;
+                walls[coord] = redraw.walls.unwrap();
+            }
+        }
+        assert_eq!(cells, maze.cells);
+        assert!(walls
+            .indexed_iter()
+            .all(|(coord, &sides)| sides == edge_walls.sides(coord)));
+        assert_eq!(Box::new(generation).finish().cells, maze.cells);
+
+        // The searcher moves from room to room, so the path has one room more than its length.
+        let shortest_length = maze.dist_grid_from(maze.start)[maze.goal].unwrap();
+        let mut searcher = create_searcher(maze, &"BFS", 12345);
+        while !searcher.terminated() {
+            searcher.advance().unwrap();
+        }
+
+        assert!(matches!(searcher.progress(), SearchProgress::Solved));
+        let num_path_rooms = searcher
+            .cell_statuses()
+            .iter()
+            .filter(|status| status.path_rank.is_some())
+            .count();
+        assert_eq!(num_path_rooms, shortest_length + 1);
+    }
+
+    #[test]
+    fn test_seeded_generation() {
+        use rand_chacha::ChaCha8Rng;
+
+        for algorithm in GENERATION_ALGORITHMS {
+            let config = GenerationConfig {
+                algorithm: algorithm.to_string(),
+                braid_factor: 0.5,
+                ..Default::default()
+            };
+            let generate = || {
+                generate_maze(
+                    MazeShape::new(21, 31),
+                    &config,
+                    &mut ChaCha8Rng::seed_from_u64(12345),
+                )
+            };
+
+            // The same seed gives the same maze.
+            let (maze1, maze2) = (generate(), generate());
+            assert_eq!(maze1.cells, maze2.cells);
+            assert_eq!((maze1.start, maze1.goal), (maze2.start, maze2.goal));
+            assert_eq!(maze1.costs, maze2.costs);
+        }
+    }
+
+    #[test]
+    fn test_difficulty_target() {
+        use rand_chacha::ChaCha8Rng;
+
+        let target = DifficultyTarget {
+            solution_length_ratio: 0.4..=1.0,
+            dead_ends: 0..=20,
+            ..Default::default()
+        };
+        let config = GenerationConfig {
+            algorithm: "Backtracker".to_string(),
+            difficulty_target: Some(target.clone()),
+            ..Default::default()
+        };
+        let shape = MazeShape::new(15, 15);
+        let rng = ChaCha8Rng::seed_from_u64(12345);
+        let maze = generate_maze(shape, &config, &mut rng.clone());
+
+        assert!(target.contains(&measure_difficulty(&maze).unwrap()));
+
+        // The animated generation searches for the same maze on its own thread.
+        let mut generation = StreamedGeneration::new(shape, config.clone(), rng.clone());
+        while !generation.terminated() {
+            generation.advance().unwrap();
+        }
+        assert_eq!(Box::new(generation).finish().cells, maze.cells);
+
+        // Finishing the generation before it is animated stops the search and still gives a maze.
+        let generation = StreamedGeneration::new(shape, config, rng);
+        let maze = Box::new(generation).finish();
+        assert!(maze.dist_grid_from(maze.start)[maze.goal].is_some());
+    }
+}
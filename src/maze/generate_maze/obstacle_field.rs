@@ -0,0 +1,109 @@
+use std::collections::VecDeque;
+
+use itertools::iproduct;
+use ndarray::prelude::*;
+use rand::prelude::*;
+
+use crate::maze::{MazeCellType, MazeShape};
+
+use super::{GenerationCanvas, PartialMazeGenerator};
+
+/// Turns into passages the fewest walls needed to connect the two cells.
+///
+/// The walls are found by a 0-1 BFS, where entering a wall costs one and entering a passage costs nothing.
+fn clear_path(
+    cells: &mut GenerationCanvas,
+    shape: MazeShape,
+    from: (usize, usize),
+    to: (usize, usize),
+) {
+    let mut costs = Array2::from_elem(cells.dim(), usize::MAX);
+    let mut prev_coords = Array2::from_elem(cells.dim(), None);
+
+    costs[from] = 0;
+    let mut coord_deque = VecDeque::from([from]);
+    while let Some(coord) = coord_deque.pop_front() {
+        if coord == to {
+            break;
+        }
+
+        for adj_coord in shape.adjacent_coordinates(coord) {
+            let is_wall = cells[adj_coord] == MazeCellType::Wall;
+            let cost = costs[coord] + usize::from(is_wall);
+
+            if cost < costs[adj_coord] {
+                costs[adj_coord] = cost;
+                prev_coords[adj_coord] = Some(coord);
+
+                if is_wall {
+                    coord_deque.push_back(adj_coord);
+                } else {
+                    coord_deque.push_front(adj_coord);
+                }
+            }
+        }
+    }
+
+    let mut coord = to;
+    while let Some(prev_coord) = prev_coords[coord] {
+        cells.set(coord, MazeCellType::Passage);
+        coord = prev_coord;
+    }
+}
+
+/// Generator of an open field with obstacles scattered at random.
+///
+/// Each cell becomes a wall with the probability `density`, except the top-left and bottom-right corners,
+/// where the start and the goal are placed.
+/// If `guarantees_path` is set, the fewest walls needed to connect the corners are removed.
+/// Otherwise, the maze may have no solution, especially when the density is high.
+pub(crate) struct ObstacleFieldGenerator {
+    pub(crate) density: f64,
+    pub(crate) guarantees_path: bool,
+}
+
+impl ObstacleFieldGenerator {
+    fn corners(shape: MazeShape) -> ((usize, usize), (usize, usize)) {
+        ((0, 0), (shape.rows - 1, shape.cols - 1))
+    }
+}
+
+impl PartialMazeGenerator for ObstacleFieldGenerator {
+    fn generate(&self, shape: MazeShape, cells: &mut GenerationCanvas, rng: &mut dyn RngCore) {
+        let MazeShape { rows, cols } = shape;
+        let density = self.density.clamp(0.0, 1.0);
+
+        for coord in iproduct!(0..rows, 0..cols) {
+            if !rng.gen_bool(density) {
+                cells.set(coord, MazeCellType::Passage);
+            }
+        }
+
+        let (corner1, corner2) = Self::corners(shape);
+        cells.set(corner1, MazeCellType::Passage);
+        cells.set(corner2, MazeCellType::Passage);
+
+        if self.guarantees_path {
+            clear_path(cells, shape, corner1, corner2);
+        }
+    }
+
+    fn follows_lattice(&self) -> bool {
+        false
+    }
+
+    fn choose_endpoints(
+        &self,
+        cells: &Array2<MazeCellType>,
+        rng: &mut dyn RngCore,
+    ) -> Option<((usize, usize), (usize, usize))> {
+        let (rows, cols) = cells.dim();
+        let (corner1, corner2) = Self::corners(MazeShape::new(rows, cols));
+
+        if rng.gen_bool(0.5) {
+            Some((corner1, corner2))
+        } else {
+            Some((corner2, corner1))
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use ndarray::prelude::*;
+use rand::prelude::*;
+
+use crate::maze::{MazeCellType, MazeShape};
+
+use super::{neighboring_cells, random_cell, GenerationCanvas, PartialMazeGenerator};
+
+/// Generator of a maze consisting of only passages and walls using randomized Prim's algorithm.
+///
+/// Starting from a random cell, the maze grows by connecting a random cell on its frontier
+/// to a random neighboring cell already in the maze.
+/// Since the maze grows evenly in all directions, the passages spread radially from the first cell.
+pub(crate) struct PrimGenerator;
+
+impl PartialMazeGenerator for PrimGenerator {
+    fn generate(&self, shape: MazeShape, cells: &mut GenerationCanvas, rng: &mut dyn RngCore) {
+        let MazeShape { rows, cols } = shape;
+
+        let mut in_frontier = Array2::from_elem((rows, cols), false);
+
+        let init_coord = random_cell(shape, rng);
+
+        let mut frontier = vec![init_coord];
+        in_frontier[init_coord] = true;
+
+        while !frontier.is_empty() {
+            let coord = frontier.swap_remove(rng.gen_range(0..frontier.len()));
+            cells.set(coord, MazeCellType::Passage);
+
+            // Connect the cell to one of the cells already in the maze.
+            let walls_to_maze = neighboring_cells(shape, coord)
+                .filter(|&(next_coord, _)| cells[next_coord] == MazeCellType::Passage)
+                .map(|(_, wall_coord)| wall_coord)
+                .collect::<Vec<_>>();
+
+            if let Some(&wall_coord) = walls_to_maze.choose(rng) {
+                cells.set(wall_coord, MazeCellType::Passage);
+            }
+
+            // Extend the frontier.
+            for (next_coord, _) in neighboring_cells(shape, coord) {
+                if !in_frontier[next_coord] {
+                    in_frontier[next_coord] = true;
+                    frontier.push(next_coord);
+                }
+            }
+        }
+    }
+}
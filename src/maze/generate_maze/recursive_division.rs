@@ -0,0 +1,102 @@
+use rand::prelude::*;
+
+use crate::maze::{MazeCellType, MazeShape};
+
+use super::{GenerationCanvas, PartialMazeGenerator};
+
+/// Rectangular area of the maze without walls inside,
+/// given by the coordinates of its top-left and bottom-right cells (both at even coordinates).
+#[derive(Debug, Clone, Copy)]
+struct Chamber {
+    top_left: (usize, usize),
+    bottom_right: (usize, usize),
+}
+
+/// Returns a random even number in `start..=end`, where both ends are even.
+fn random_even(start: usize, end: usize, rng: &mut dyn RngCore) -> usize {
+    start + 2 * rng.gen_range(0..=(end - start) / 2)
+}
+
+/// Returns a random odd number between `start` and `end`, where both ends are even.
+fn random_odd(start: usize, end: usize, rng: &mut dyn RngCore) -> usize {
+    start + 2 * rng.gen_range(0..(end - start) / 2) + 1
+}
+
+/// Generator of a maze consisting of only passages and walls using recursive division.
+///
+/// Starting from a field without walls, each chamber is divided in two by a wall at odd coordinates
+/// with a gap at even coordinates, until every chamber is one cell wide.
+/// Unlike the other generators, walls are added instead of passages being dug.
+pub(crate) struct RecursiveDivisionGenerator;
+
+impl PartialMazeGenerator for RecursiveDivisionGenerator {
+    fn generate(&self, shape: MazeShape, cells: &mut GenerationCanvas, rng: &mut dyn RngCore) {
+        let MazeShape { rows, cols } = shape;
+
+        cells.fill(MazeCellType::Passage);
+
+        let mut chambers = vec![Chamber {
+            top_left: (0, 0),
+            bottom_right: (rows - 1, cols - 1),
+        }];
+
+        while let Some(chamber) = chambers.pop() {
+            let Chamber {
+                top_left: (top, left),
+                bottom_right: (bottom, right),
+            } = chamber;
+
+            let height = bottom - top;
+            let width = right - left;
+
+            if height == 0 || width == 0 {
+                continue;
+            }
+
+            // Divide across the longer side so that the chambers do not become long and thin.
+            let divide_horizontally = match height.cmp(&width) {
+                std::cmp::Ordering::Greater => true,
+                std::cmp::Ordering::Less => false,
+                std::cmp::Ordering::Equal => rng.gen_bool(0.5),
+            };
+
+            if divide_horizontally {
+                let wall_row = random_odd(top, bottom, rng);
+                let gap_col = random_even(left, right, rng);
+
+                for col in left..=right {
+                    if col != gap_col {
+                        cells.set((wall_row, col), MazeCellType::Wall);
+                    }
+                }
+
+                chambers.push(Chamber {
+                    top_left: (top, left),
+                    bottom_right: (wall_row - 1, right),
+                });
+                chambers.push(Chamber {
+                    top_left: (wall_row + 1, left),
+                    bottom_right: (bottom, right),
+                });
+            } else {
+                let wall_col = random_odd(left, right, rng);
+                let gap_row = random_even(top, bottom, rng);
+
+                for row in top..=bottom {
+                    if row != gap_row {
+                        cells.set((row, wall_col), MazeCellType::Wall);
+                    }
+                }
+
+                chambers.push(Chamber {
+                    top_left: (top, left),
+                    bottom_right: (bottom, wall_col - 1),
+                });
+                chambers.push(Chamber {
+                    top_left: (top, wall_col + 1),
+                    bottom_right: (bottom, right),
+                });
+            }
+        }
+    }
+}
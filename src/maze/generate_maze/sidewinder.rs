@@ -0,0 +1,39 @@
+use rand::prelude::*;
+
+use crate::maze::{MazeCellType, MazeShape};
+
+use super::{GenerationCanvas, PartialMazeGenerator};
+
+/// Generator of a maze consisting of only passages and walls using the sidewinder algorithm.
+///
+/// The top row becomes a straight corridor.
+/// Each of the other rows is split at random into horizontal runs of cells,
+/// and each run is connected to the row above at one random cell.
+/// Every cell has a path to the top row that never goes down.
+pub(crate) struct SidewinderGenerator;
+
+impl PartialMazeGenerator for SidewinderGenerator {
+    fn generate(&self, shape: MazeShape, cells: &mut GenerationCanvas, rng: &mut dyn RngCore) {
+        let MazeShape { rows, cols } = shape;
+
+        for row in (0..rows).step_by(2) {
+            let mut run_start_col = 0;
+
+            for col in (0..cols).step_by(2) {
+                cells.set((row, col), MazeCellType::Passage);
+
+                let is_last_col = col + 1 >= cols;
+                let closes_run = row > 0 && (is_last_col || rng.gen_bool(0.5));
+
+                if closes_run {
+                    // Connect a random cell of the run to the row above.
+                    let up_col = run_start_col + 2 * rng.gen_range(0..=(col - run_start_col) / 2);
+                    cells.set((row - 1, up_col), MazeCellType::Passage);
+                    run_start_col = col + 2;
+                } else if !is_last_col {
+                    cells.set((row, col + 1), MazeCellType::Passage);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use ndarray::prelude::*;
+use rand::prelude::*;
+
+use crate::maze::{MazeCellType, MazeShape};
+
+use super::{keep_largest_region, GenerationCanvas, PartialMazeGenerator};
+
+/// Spacing of the random values of the coarsest octave of the noise, in cells.
+const NOISE_SCALE: f64 = 8.0;
+
+/// Number of octaves of the noise, each with half the spacing and half the amplitude of the previous one.
+const NUM_OCTAVES: usize = 3;
+
+/// Height above which the terrain is an impassable wall.
+const WALL_HEIGHT: f64 = 0.75;
+
+/// Extra cost of entering a cell just below the wall height.
+const MAX_HILL_COST: f64 = 4.0;
+
+/// Half the width of the rivers, as a range of the river noise around its middle value.
+const RIVER_HALF_WIDTH: f64 = 0.04;
+
+/// Extra cost of entering a river.
+const RIVER_COST: usize = 6;
+
+fn lerp(value1: f64, value2: f64, ratio: f64) -> f64 {
+    value1 + (value2 - value1) * ratio
+}
+
+fn smoothstep(ratio: f64) -> f64 {
+    ratio * ratio * (3.0 - 2.0 * ratio)
+}
+
+/// Generates smooth random values in the range from 0 to 1 by adding up octaves of value noise.
+fn generate_value_noise(shape: MazeShape, rng: &mut dyn RngCore) -> Array2<f64> {
+    let MazeShape { rows, cols } = shape;
+
+    let mut noise = Array2::zeros((rows, cols));
+    let mut scale = NOISE_SCALE;
+    let mut amplitude = 1.0;
+
+    for _ in 0..NUM_OCTAVES {
+        // Random values at the corners of squares of the size `scale` covering the maze.
+        let lattice_rows = (rows as f64 / scale).ceil() as usize + 2;
+        let lattice_cols = (cols as f64 / scale).ceil() as usize + 2;
+        let lattice =
+            Array2::from_shape_simple_fn((lattice_rows, lattice_cols), || rng.gen::<f64>());
+
+        // Interpolate the values smoothly.
+        for ((row, col), value) in noise.indexed_iter_mut() {
+            let (y, x) = (row as f64 / scale, col as f64 / scale);
+            let (lattice_row, lattice_col) = (y as usize, x as usize);
+            let (ratio_y, ratio_x) = (smoothstep(y.fract()), smoothstep(x.fract()));
+
+            let top = lerp(
+                lattice[(lattice_row, lattice_col)],
+                lattice[(lattice_row, lattice_col + 1)],
+                ratio_x,
+            );
+            let bottom = lerp(
+                lattice[(lattice_row + 1, lattice_col)],
+                lattice[(lattice_row + 1, lattice_col + 1)],
+                ratio_x,
+            );
+            *value += amplitude * lerp(top, bottom, ratio_y);
+        }
+
+        scale /= 2.0;
+        amplitude /= 2.0;
+    }
+
+    let min_value = noise.fold(f64::INFINITY, |acc, &value| acc.min(value));
+    let max_value = noise.fold(f64::NEG_INFINITY, |acc, &value| acc.max(value));
+    if max_value > min_value {
+        noise.mapv_inplace(|value| (value - min_value) / (max_value - min_value));
+    }
+
+    noise
+}
+
+/// Generator of a terrain with hills and rivers using value noise.
+///
+/// The cost of entering a cell rises with the height given by the noise,
+/// and the highest places become walls.
+/// Rivers run where a second noise is close to its middle value, and are expensive to cross.
+/// Only the largest connected region of passages is kept.
+pub(crate) struct TerrainGenerator;
+
+impl PartialMazeGenerator for TerrainGenerator {
+    fn generate(&self, shape: MazeShape, canvas: &mut GenerationCanvas, rng: &mut dyn RngCore) {
+        self.generate_with_costs(shape, canvas, rng);
+    }
+
+    fn generate_with_costs(
+        &self,
+        shape: MazeShape,
+        canvas: &mut GenerationCanvas,
+        rng: &mut dyn RngCore,
+    ) -> Array2<usize> {
+        let MazeShape { rows, cols } = shape;
+
+        let heights = generate_value_noise(shape, rng);
+        let rivers = generate_value_noise(shape, rng);
+
+        let mut cells = heights.mapv(|height| {
+            if height >= WALL_HEIGHT {
+                MazeCellType::Wall
+            } else {
+                MazeCellType::Passage
+            }
+        });
+
+        let costs = Array2::from_shape_fn((rows, cols), |coord| {
+            let hill_cost = (heights[coord] / WALL_HEIGHT * MAX_HILL_COST).round() as usize;
+            let river_cost = if (rivers[coord] - 0.5).abs() < RIVER_HALF_WIDTH {
+                RIVER_COST
+            } else {
+                0
+            };
+
+            1 + hill_cost + river_cost
+        });
+
+        // A maze too small for the terrain to have two connected cells is left open.
+        if keep_largest_region(&mut cells, shape) < 2 {
+            cells.fill(MazeCellType::Passage);
+        }
+        canvas.overwrite(&cells);
+
+        costs
+    }
+
+    fn follows_lattice(&self) -> bool {
+        false
+    }
+}
@@ -0,0 +1,48 @@
+use itertools::{iproduct, Itertools};
+use ndarray::prelude::*;
+use rand::prelude::*;
+
+use crate::maze::{MazeCellType, MazeShape};
+
+use super::{neighboring_cells, random_cell, GenerationCanvas, PartialMazeGenerator};
+
+/// Generator of a maze consisting of only passages and walls using Wilson's algorithm.
+///
+/// Starting from a maze of one random cell, a random walk is taken from each cell not yet in the maze
+/// until it reaches the maze, and the walk with its loops erased is dug as a passage.
+/// Every possible maze is generated with equal probability.
+pub(crate) struct WilsonGenerator;
+
+impl PartialMazeGenerator for WilsonGenerator {
+    fn generate(&self, shape: MazeShape, cells: &mut GenerationCanvas, rng: &mut dyn RngCore) {
+        let MazeShape { rows, cols } = shape;
+
+        cells.set(random_cell(shape, rng), MazeCellType::Passage);
+
+        // The next cell and the wall in between, last chosen from each cell during the walk.
+        let mut next_steps = Array2::from_elem((rows, cols), None);
+
+        let mut walk_starts = iproduct!((0..rows).step_by(2), (0..cols).step_by(2)).collect_vec();
+        walk_starts.shuffle(rng);
+
+        for walk_start in walk_starts {
+            // Walk randomly until reaching the maze.
+            // Only the last choice in each cell is remembered, which erases the loops.
+            let mut coord = walk_start;
+            while cells[coord] == MazeCellType::Wall {
+                let next_step = neighboring_cells(shape, coord).choose(rng).unwrap();
+                next_steps[coord] = Some(next_step);
+                coord = next_step.0;
+            }
+
+            // Dig along the walk without loops.
+            let mut coord = walk_start;
+            while cells[coord] == MazeCellType::Wall {
+                let (next_coord, wall_coord) = next_steps[coord].unwrap();
+                cells.set(coord, MazeCellType::Passage);
+                cells.set(wall_coord, MazeCellType::Passage);
+                coord = next_coord;
+            }
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use itertools::Itertools;
+use ndarray::prelude::*;
+
+use super::analysis::count_passable_neighbors;
+use super::{MazeCellType, MazeGrid};
+
+/// Corridor from one node of the junction graph to another.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct JunctionEdge {
+    /// Index of the node at the end of the corridor.
+    pub(crate) to: usize,
+
+    /// Total cost of entering the cells of the corridor and the node at its end.
+    pub(crate) cost: usize,
+
+    /// Cells of the corridor between the nodes, in order from the node where the edge starts.
+    pub(crate) corridor: Vec<(usize, usize)>,
+}
+
+/// Weighted graph compressing a maze, whose nodes are the start, the goal and the passable cells
+/// which do not have exactly two passable neighbors, that is, junctions and dead ends.
+///
+/// The runs of other passable cells are corridors between two nodes, and become the weighted edges.
+#[derive(Debug, Clone)]
+pub(crate) struct JunctionGraph {
+    /// Cells of the nodes.
+    pub(crate) nodes: Vec<(usize, usize)>,
+
+    /// Index of the node at each cell, if the cell is a node.
+    pub(crate) node_indices: Array2<Option<usize>>,
+
+    /// Edges leaving each node, one for each corridor from the node to another node.
+    pub(crate) edges: Vec<Vec<JunctionEdge>>,
+}
+
+/// Compresses the maze into the graph of its junctions, dead ends, start and goal.
+///
+/// A corridor leading back to the node where it starts is left out, since no shortest path uses it.
+pub(crate) fn extract_junction_graph(maze: &MazeGrid) -> JunctionGraph {
+    let nodes = maze
+        .cells
+        .indexed_iter()
+        .filter(|&(coord, &cell_type)| match cell_type {
+            MazeCellType::Passage => count_passable_neighbors(maze, coord) != 2,
+            MazeCellType::Wall => false,
+            MazeCellType::Start | MazeCellType::Goal => true,
+        })
+        .map(|(coord, _)| coord)
+        .collect_vec();
+
+    let mut node_indices = Array2::from_elem((maze.shape.rows, maze.shape.cols), None);
+    for (node_idx, &coord) in nodes.iter().enumerate() {
+        node_indices[coord] = Some(node_idx);
+    }
+
+    // Follow the corridor from the node through the first cell until another node is reached.
+    let follow_corridor = |node: (usize, usize), first_coord: (usize, usize)| {
+        let mut corridor = vec![];
+        let mut cost = maze.costs[first_coord];
+        let (mut prev_coord, mut coord) = (node, first_coord);
+
+        while node_indices[coord].is_none() {
+            // The cells in a corridor have exactly two passable neighbors.
+            let next_coord = maze
+                .passable_neighbors(coord)
+                .find(|&adj_coord| adj_coord != prev_coord)
+                .unwrap();
+
+            corridor.push(coord);
+            cost += maze.costs[next_coord];
+            (prev_coord, coord) = (coord, next_coord);
+        }
+
+        (coord != node).then(|| JunctionEdge {
+            to: node_indices[coord].unwrap(),
+            cost,
+            corridor,
+        })
+    };
+
+    let edges = nodes
+        .iter()
+        .map(|&node| {
+            maze.passable_neighbors(node)
+                .filter_map(|first_coord| follow_corridor(node, first_coord))
+                .collect_vec()
+        })
+        .collect_vec();
+
+    JunctionGraph {
+        nodes,
+        node_indices,
+        edges,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_junction_graph() {
+        let maze: MazeGrid = crate::maze::TEST_MAZE.parse().unwrap();
+
+        let graph = extract_junction_graph(&maze);
+
+        // The start, the junction, the goal and the dead end at the bottom right.
+        assert_eq!(graph.nodes, vec![(0, 0), (0, 2), (2, 0), (2, 4)]);
+
+        let junction_idx = graph.node_indices[(0, 2)].unwrap();
+        let edges = &graph.edges[junction_idx];
+        assert_eq!(edges.len(), 3);
+        assert_eq!(edges.iter().map(|edge| edge.cost).sum::<usize>(), 2 + 4 + 4);
+
+        let edge_to_goal = edges
+            .iter()
+            .find(|edge| graph.nodes[edge.to] == maze.goal)
+            .unwrap();
+        assert_eq!(edge_to_goal.corridor, vec![(1, 2), (2, 2), (2, 1)]);
+
+        // Each corridor is an edge from both of its ends.
+        assert_eq!(
+            graph.edges.iter().map(|edges| edges.len()).sum::<usize>(),
+            6
+        );
+    }
+}
@@ -0,0 +1,154 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashSet};
+
+use ndarray::prelude::*;
+
+use super::MazeGrid;
+
+/// Move from a cell to an adjacent cell.
+type CellMove = ((usize, usize), (usize, usize));
+
+/// Finds the cheapest path from `spur` to the goal by Dijkstra's algorithm
+/// without passing through the blocked cells or the blocked moves.
+fn find_spur_path(
+    maze: &MazeGrid,
+    spur: (usize, usize),
+    blocked_cells: &Array2<bool>,
+    blocked_moves: &HashSet<CellMove>,
+) -> Option<Vec<(usize, usize)>> {
+    let MazeGrid { shape, .. } = *maze;
+
+    let mut cost_heap = BinaryHeap::from([Reverse((0, spur, None))]);
+    let mut prev_coords: Array2<Option<Option<(usize, usize)>>> =
+        Array2::from_elem((shape.rows, shape.cols), None);
+
+    while let Some(Reverse((cost, coord, prev_coord))) = cost_heap.pop() {
+        if prev_coords[coord].is_some() {
+            continue;
+        }
+
+        prev_coords[coord] = Some(prev_coord);
+
+        if coord == maze.goal {
+            // Restore the path by following the previous cells.
+            let mut path = vec![coord];
+            while let Some(Some(prev_coord)) = prev_coords[*path.last().unwrap()] {
+                path.push(prev_coord);
+            }
+            path.reverse();
+
+            return Some(path);
+        }
+
+        for adj_coord in maze.passable_neighbors(coord) {
+            if !blocked_cells[adj_coord] && !blocked_moves.contains(&(coord, adj_coord)) {
+                cost_heap.push(Reverse((
+                    cost + maze.costs[adj_coord],
+                    adj_coord,
+                    Some(coord),
+                )));
+            }
+        }
+    }
+
+    None
+}
+
+/// Finds up to `k` shortest loop-free paths from the start to the goal using Yen's algorithm.
+///
+/// The paths are returned in order of cost, which is the order of length on a maze without terrain,
+/// each including both the start and the goal.
+/// A perfect maze has only one loop-free path, so more paths are found only on mazes with loops.
+pub(crate) fn find_k_shortest_paths(maze: &MazeGrid, k: usize) -> Vec<Vec<(usize, usize)>> {
+    let MazeGrid { shape, .. } = *maze;
+
+    if k == 0 {
+        return vec![];
+    }
+
+    let no_blocked_cells = Array2::from_elem((shape.rows, shape.cols), false);
+    let Some(shortest_path) = find_spur_path(maze, maze.start, &no_blocked_cells, &HashSet::new())
+    else {
+        return vec![];
+    };
+
+    let mut found_paths = HashSet::from([shortest_path.clone()]);
+    let mut paths = vec![shortest_path];
+    let mut candidate_heap = BinaryHeap::new();
+
+    while paths.len() < k {
+        let last_path = paths.last().unwrap();
+
+        // Deviate from the last path at each of its cells.
+        for spur_idx in 0..last_path.len() - 1 {
+            let spur = last_path[spur_idx];
+            let root_path = &last_path[..=spur_idx];
+
+            // Forbid the moves already taken by the paths sharing the same root.
+            let blocked_moves = paths
+                .iter()
+                .filter(|path| path.len() > spur_idx + 1 && path[..=spur_idx] == *root_path)
+                .map(|path| (path[spur_idx], path[spur_idx + 1]))
+                .collect::<HashSet<_>>();
+
+            // Forbid going back to the root so that the path does not loop.
+            let mut blocked_cells = no_blocked_cells.clone();
+            for &coord in &root_path[..spur_idx] {
+                blocked_cells[coord] = true;
+            }
+
+            if let Some(spur_path) = find_spur_path(maze, spur, &blocked_cells, &blocked_moves) {
+                let mut candidate = root_path[..spur_idx].to_vec();
+                candidate.extend(spur_path);
+
+                if found_paths.insert(candidate.clone()) {
+                    candidate_heap.push(Reverse((maze.path_cost(&candidate), candidate)));
+                }
+            }
+        }
+
+        // Adopt the cheapest of the candidates.
+        let Some(Reverse((_, path))) = candidate_heap.pop() else {
+            break;
+        };
+        paths.push(path);
+    }
+
+    paths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_k_shortest_paths() {
+        let maze: MazeGrid = "S . . . .\n. # . # .\n. . . . G".parse().unwrap();
+
+        let paths = find_k_shortest_paths(&maze, 10);
+        let path_lengths = paths.iter().map(|path| path.len() - 1).collect::<Vec<_>>();
+
+        // Three routes going down once, and one route zigzagging through all columns.
+        assert_eq!(path_lengths, vec![6, 6, 6, 10]);
+        assert_eq!(paths.iter().collect::<HashSet<_>>().len(), paths.len());
+    }
+
+    #[test]
+    fn test_find_k_shortest_paths_with_terrain() {
+        let mut maze: MazeGrid = "S . . . .\n. # . # .\n. . . . G".parse().unwrap();
+        maze.costs[(0, 2)] = 10;
+
+        let paths = find_k_shortest_paths(&maze, 10);
+        let path_costs = paths
+            .iter()
+            .map(|path| maze.path_cost(path))
+            .collect::<Vec<_>>();
+
+        // The routes along the top row pass through the costly cell.
+        assert_eq!(path_costs, vec![6, 15, 15, 19]);
+        assert_eq!(
+            paths[0],
+            vec![(0, 0), (1, 0), (2, 0), (2, 1), (2, 2), (2, 3), (2, 4)]
+        );
+    }
+}
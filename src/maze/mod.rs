@@ -1,13 +1,47 @@
+pub(crate) mod analysis;
+pub(crate) mod edge_walls;
 pub(crate) mod generate_maze;
+pub(crate) mod junction_graph;
+pub(crate) mod k_shortest_paths;
+pub(crate) mod obstacle;
 pub(crate) mod searcher;
+pub(crate) mod shortest_path_count;
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
 
 use itertools::Itertools;
 use ndarray::prelude::*;
 
 use crate::utils::palette;
 
+use edge_walls::EdgeWalls;
+use obstacle::MovingObstacles;
+
 pub(crate) const ADJACENT_DISPLACEMENT: [(usize, usize); 4] = [(!0, 0), (0, !0), (0, 1), (1, 0)];
 
+/// Returns the number of steps between the cells when there are no walls.
+pub(crate) fn calculate_manhattan_distance(
+    coord1: (usize, usize),
+    coord2: (usize, usize),
+) -> usize {
+    coord1.0.abs_diff(coord2.0) + coord1.1.abs_diff(coord2.1)
+}
+
+/// A small maze with a dead end, a junction and a loop, shared by the tests.
+#[cfg(test)]
+pub(crate) const TEST_MAZE: &str = "S . . . .\n# # . # .\nG . . # .";
+
+/// Colors of the highlighted paths, in order of the rank of the path.
+const PATH_COLORS: [slint::Color; 6] = [
+    palette::YELLOW,
+    palette::ORANGE,
+    palette::PINK,
+    palette::CYAN,
+    palette::BRIGHT_PURPLE,
+    palette::BEIGE,
+];
+
 /// Represents the role of a cell on the maze.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(crate) enum MazeCellType {
@@ -82,6 +116,34 @@ impl MazeShape {
         })
     }
 
+    /// Returns the number of steps from `origin` to each cell by the moves between adjacent cells satisfying `can_move`,
+    /// or `None` for the cells not reachable.
+    pub(crate) fn dist_grid_from<F>(
+        &self,
+        origin: (usize, usize),
+        can_move: F,
+    ) -> Array2<Option<usize>>
+    where
+        F: Fn((usize, usize), (usize, usize)) -> bool,
+    {
+        let mut dist_grid = Array2::from_elem((self.rows, self.cols), None);
+        dist_grid[origin] = Some(0);
+
+        let mut coord_queue = VecDeque::from([origin]);
+        while let Some(coord) = coord_queue.pop_front() {
+            let dist = dist_grid[coord].unwrap();
+
+            for adj_coord in self.adjacent_coordinates(coord) {
+                if dist_grid[adj_coord].is_none() && can_move(coord, adj_coord) {
+                    dist_grid[adj_coord] = Some(dist + 1);
+                    coord_queue.push_back(adj_coord);
+                }
+            }
+        }
+
+        dist_grid
+    }
+
     #[allow(unused)]
     pub(crate) fn area(&self) -> usize {
         self.rows * self.cols
@@ -108,6 +170,105 @@ pub(crate) struct MazeGrid {
     pub(crate) cells: Array2<MazeCellType>,
     pub(crate) start: (usize, usize),
     pub(crate) goal: (usize, usize),
+
+    /// Cost of entering each cell (one for plain ground).
+    /// Only searchers that find the cheapest path take them into account.
+    pub(crate) costs: Array2<usize>,
+
+    /// Guards patrolling the maze.
+    /// Only searchers that keep track of time steps take them into account.
+    pub(crate) obstacles: MovingObstacles,
+
+    /// Walls on the edges between the cells, for a maze drawn with thin walls.
+    pub(crate) edge_walls: Option<EdgeWalls>,
+
+    /// Whether the passable cells are the cells at even coordinates and some of the walls between them,
+    /// so that only the cells at even coordinates may hold the start and the goal.
+    /// A maze with thin walls holds only its rooms, any of which may hold them.
+    pub(crate) follows_lattice: bool,
+}
+
+impl MazeGrid {
+    /// Returns whether a step from the cell to the adjacent cell is possible,
+    /// which requires the adjacent cell to be passable and no wall on the edge between them.
+    pub(crate) fn can_move(&self, from: (usize, usize), to: (usize, usize)) -> bool {
+        self.cells[to].is_passable()
+            && !self
+                .edge_walls
+                .as_ref()
+                .is_some_and(|edge_walls| edge_walls.separates(from, to))
+    }
+
+    /// Returns the adjacent cells that can be stepped into from the cell.
+    pub(crate) fn passable_neighbors(
+        &self,
+        coord: (usize, usize),
+    ) -> impl '_ + Iterator<Item = (usize, usize)> {
+        self.shape
+            .adjacent_coordinates(coord)
+            .filter(move |&adj_coord| self.can_move(coord, adj_coord))
+    }
+
+    /// Returns the number of steps from `origin` to each cell, or `None` for the cells not reachable.
+    pub(crate) fn dist_grid_from(&self, origin: (usize, usize)) -> Array2<Option<usize>> {
+        self.shape
+            .dist_grid_from(origin, |from, to| self.can_move(from, to))
+    }
+
+    /// Returns one of the shortest paths from `from` to `to`, including both ends.
+    pub(crate) fn find_shortest_path(
+        &self,
+        from: (usize, usize),
+        to: (usize, usize),
+    ) -> Option<Vec<(usize, usize)>> {
+        let dist_grid = self.dist_grid_from(to);
+        dist_grid[from]?;
+
+        // Step to a neighbor one step nearer to `to` each time.
+        let mut path = vec![from];
+        while let Some(dist @ 1..) = dist_grid[*path.last().unwrap()] {
+            let next_coord = self
+                .passable_neighbors(*path.last().unwrap())
+                .find(|&adj_coord| dist_grid[adj_coord] == Some(dist - 1))
+                .unwrap();
+            path.push(next_coord);
+        }
+
+        Some(path)
+    }
+
+    /// Returns whether any cell costs more than plain ground, so that the cheapest paths may be longer than the shortest ones.
+    pub(crate) fn has_terrain(&self) -> bool {
+        self.costs.iter().any(|&cost| cost != 1)
+    }
+
+    /// Returns the cost of moving along the path, the sum of the costs of the cells entered after the first one.
+    pub(crate) fn path_cost(&self, path: &[(usize, usize)]) -> usize {
+        path.iter().skip(1).map(|&coord| self.costs[coord]).sum()
+    }
+
+    /// Returns the lowest cost of moving from `origin` to each cell by Dijkstra's algorithm,
+    /// or `None` for the cells not reachable.
+    pub(crate) fn cost_grid_from(&self, origin: (usize, usize)) -> Array2<Option<usize>> {
+        let mut cost_grid = Array2::from_elem((self.shape.rows, self.shape.cols), None);
+        let mut cost_heap = BinaryHeap::from([Reverse((0, origin))]);
+
+        while let Some(Reverse((cost, coord))) = cost_heap.pop() {
+            if cost_grid[coord].is_some() {
+                continue;
+            }
+
+            cost_grid[coord] = Some(cost);
+
+            for adj_coord in self.passable_neighbors(coord) {
+                if cost_grid[adj_coord].is_none() {
+                    cost_heap.push(Reverse((cost + self.costs[adj_coord], adj_coord)));
+                }
+            }
+        }
+
+        cost_grid
+    }
 }
 
 impl std::fmt::Display for MazeGrid {
@@ -122,13 +283,84 @@ impl std::fmt::Display for MazeGrid {
     }
 }
 
+/// Error returned if a string does not represent a maze.
+#[derive(Debug)]
+pub(crate) struct ParseMazeError;
+
+impl std::fmt::Display for ParseMazeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "The maze must be a rectangle of cells with exactly one start and one goal."
+        )
+    }
+}
+
+impl std::error::Error for ParseMazeError {}
+
+impl std::str::FromStr for MazeGrid {
+    type Err = ParseMazeError;
+
+    /// Parses a maze in the format written by `Display`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let rows = s
+            .lines()
+            .map(|line| {
+                line.split_whitespace()
+                    .map(|cell| match cell {
+                        "." => Ok(MazeCellType::Passage),
+                        "#" => Ok(MazeCellType::Wall),
+                        "S" => Ok(MazeCellType::Start),
+                        "G" => Ok(MazeCellType::Goal),
+                        _ => Err(ParseMazeError),
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let shape = MazeShape::new(rows.len(), rows.first().map_or(0, |row| row.len()));
+        let cells = Array2::from_shape_vec((shape.rows, shape.cols), rows.concat())
+            .map_err(|_| ParseMazeError)?;
+
+        let find_unique_cell = |cell_type: MazeCellType| {
+            cells
+                .indexed_iter()
+                .filter(|&(_, &cell)| cell == cell_type)
+                .map(|(coord, _)| coord)
+                .exactly_one()
+                .map_err(|_| ParseMazeError)
+        };
+        let start = find_unique_cell(MazeCellType::Start)?;
+        let goal = find_unique_cell(MazeCellType::Goal)?;
+
+        Ok(Self {
+            shape,
+            cells,
+            start,
+            goal,
+            costs: Array2::ones((shape.rows, shape.cols)),
+            obstacles: MovingObstacles::default(),
+            edge_walls: None,
+            follows_lattice: false,
+        })
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub(crate) struct MazeCellStatus {
     pub(crate) cell_type: MazeCellType,
     pub(crate) stay: bool,
     pub(crate) visited: bool,
     pub(crate) footprint: bool,
-    pub(crate) on_path: bool,
+
+    /// Rank of the highlighted path passing through the cell (0 for the shortest one).
+    pub(crate) path_rank: Option<usize>,
+
+    /// Value between 0 and 1 drawn on the cell as a heatmap.
+    pub(crate) heat: Option<f32>,
+
+    /// Direction of the arrow drawn on the cell, as an index of `ADJACENT_DISPLACEMENT`.
+    pub(crate) direction: Option<usize>,
 }
 
 impl MazeCellStatus {
@@ -138,7 +370,9 @@ impl MazeCellStatus {
             stay: false,
             visited: false,
             footprint: false,
-            on_path: false,
+            path_rank: None,
+            heat: None,
+            direction: None,
         }
     }
 
@@ -150,8 +384,8 @@ impl MazeCellStatus {
             MazeCellType::Goal => return palette::BLUE,
         }
 
-        if self.on_path {
-            return palette::YELLOW;
+        if let Some(path_rank) = self.path_rank {
+            return PATH_COLORS[path_rank % PATH_COLORS.len()];
         }
 
         if self.visited {
@@ -173,6 +407,18 @@ impl MazeCellStatus {
     }
 
     pub(crate) fn set_on_path(&mut self, on_path: bool) {
-        self.on_path = on_path;
+        self.path_rank = if on_path { Some(0) } else { None };
+    }
+
+    pub(crate) fn set_path_rank(&mut self, path_rank: Option<usize>) {
+        self.path_rank = path_rank;
+    }
+
+    pub(crate) fn set_heat(&mut self, heat: Option<f32>) {
+        self.heat = heat;
+    }
+
+    pub(crate) fn set_direction(&mut self, direction: Option<usize>) {
+        self.direction = direction;
     }
 }
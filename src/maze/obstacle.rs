@@ -0,0 +1,256 @@
+use itertools::Itertools;
+use ndarray::prelude::*;
+use rand::prelude::*;
+
+use super::{MazeCellType, MazeGrid, MazeShape};
+
+/// Number of cells in the maze per patrolling guard.
+const AREA_PER_PATROL: usize = 150;
+
+/// Maximum number of steps a guard walks away from its initial position.
+const MAX_PATROL_REACH: usize = 6;
+
+/// Guard that periodically walks along a scripted route.
+#[derive(Debug, Clone)]
+pub(crate) struct Patrol {
+    /// Cells visited at each time step.
+    /// After the last cell, the guard returns to the first cell.
+    route: Vec<(usize, usize)>,
+}
+
+impl Patrol {
+    /// Creates a guard that walks along the cells of `walk` and then back again.
+    pub(crate) fn back_and_forth(walk: Vec<(usize, usize)>) -> Self {
+        assert!(!walk.is_empty(), "The route of a guard must not be empty.");
+
+        let mut route = walk.clone();
+        route.extend(walk.iter().rev().skip(1).take(walk.len().saturating_sub(2)));
+
+        Self { route }
+    }
+
+    /// Returns the number of time steps until the guard returns to its initial position.
+    pub(crate) fn period(&self) -> usize {
+        self.route.len()
+    }
+
+    /// Returns the position of the guard at `time_step`.
+    pub(crate) fn position(&self, time_step: usize) -> (usize, usize) {
+        self.route[time_step % self.route.len()]
+    }
+}
+
+/// Obstacles moving in lockstep with the time step of the maze search.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct MovingObstacles {
+    patrols: Vec<Patrol>,
+}
+
+impl MovingObstacles {
+    pub(crate) fn new(patrols: Vec<Patrol>) -> Self {
+        Self { patrols }
+    }
+
+    /// Returns whether there are no guards.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.patrols.is_empty()
+    }
+
+    /// Returns the number of time steps after which all guards are back at their initial positions.
+    pub(crate) fn period(&self) -> usize {
+        self.patrols
+            .iter()
+            .fold(1, |acc, patrol| lcm(acc, patrol.period()))
+    }
+
+    /// Returns the positions of all guards at `time_step`.
+    pub(crate) fn positions(&self, time_step: usize) -> impl '_ + Iterator<Item = (usize, usize)> {
+        self.patrols
+            .iter()
+            .map(move |patrol| patrol.position(time_step))
+    }
+
+    /// Returns whether moving from `from` at `time_step` to `to` at the next time step
+    /// avoids all guards, including guards passing through the agent in the opposite direction.
+    pub(crate) fn is_safe_move(
+        &self,
+        from: (usize, usize),
+        to: (usize, usize),
+        time_step: usize,
+    ) -> bool {
+        self.patrols.iter().all(|patrol| {
+            let current = patrol.position(time_step);
+            let next = patrol.position(time_step + 1);
+
+            next != to && !(current == to && next == from)
+        })
+    }
+}
+
+fn lcm(a: usize, b: usize) -> usize {
+    let gcd = {
+        let (mut x, mut y) = (a, b);
+        while y != 0 {
+            (x, y) = (y, x % y);
+        }
+        x
+    };
+
+    a / gcd * b
+}
+
+/// Places guards patrolling back and forth along the passages of the maze.
+///
+/// Guards never step on the start or the goal.
+/// To keep the maze solvable, each route crosses the shortest path of the maze at most once,
+/// and routes of different guards are kept apart.
+pub(crate) fn place_patrols<R>(maze: &MazeGrid, rng: &mut R) -> MovingObstacles
+where
+    R: Rng,
+{
+    let MazeShape { rows, cols } = maze.shape;
+
+    let mut on_path = Array2::from_elem((rows, cols), false);
+    if let Some(path) = maze.find_shortest_path(maze.start, maze.goal) {
+        for coord in path {
+            on_path[coord] = true;
+        }
+    }
+
+    // Cells on or next to the route of an already placed guard.
+    let mut reserved = Array2::from_elem((rows, cols), false);
+
+    let mut passages = maze
+        .cells
+        .indexed_iter()
+        .filter(|&(_, &cell_type)| cell_type == MazeCellType::Passage)
+        .map(|(coord, _)| coord)
+        .collect_vec();
+    passages.shuffle(rng);
+
+    let num_patrols = maze.shape.area() / AREA_PER_PATROL;
+    let mut patrols = vec![];
+
+    for init_coord in passages {
+        if patrols.len() == num_patrols {
+            break;
+        }
+
+        if reserved[init_coord] {
+            continue;
+        }
+
+        // Randomly walk along passages without visiting the same cell twice.
+        let mut walk = vec![init_coord];
+        while walk.len() <= MAX_PATROL_REACH {
+            let crosses_path = walk.iter().any(|&coord| on_path[coord]);
+
+            let candidates = maze
+                .shape
+                .adjacent_coordinates(*walk.last().unwrap())
+                .filter(|&adj_coord| {
+                    maze.cells[adj_coord] == MazeCellType::Passage
+                        && maze.can_move(*walk.last().unwrap(), adj_coord)
+                        && !reserved[adj_coord]
+                        && !walk.contains(&adj_coord)
+                        && (!crosses_path || !on_path[adj_coord])
+                })
+                .collect_vec();
+
+            match candidates.choose(rng) {
+                Some(&next_coord) => walk.push(next_coord),
+                None => break,
+            }
+        }
+
+        // A guard standing still could block the path forever.
+        if walk.len() < 2 {
+            continue;
+        }
+
+        for &coord in &walk {
+            reserved[coord] = true;
+            for adj_coord in maze.shape.adjacent_coordinates(coord) {
+                reserved[adj_coord] = true;
+            }
+        }
+
+        patrols.push(Patrol::back_and_forth(walk));
+    }
+
+    MovingObstacles::new(patrols)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+    use crate::maze::generate_maze::{generate_maze, GenerationConfig};
+
+    #[test]
+    fn test_positions() {
+        let obstacles = MovingObstacles::new(vec![
+            Patrol::back_and_forth(vec![(0, 0), (0, 1), (0, 2)]),
+            Patrol::back_and_forth(vec![(2, 0), (2, 1)]),
+        ]);
+
+        // The routes take 4 and 2 time steps.
+        assert_eq!(obstacles.period(), 4);
+        assert_eq!(
+            (0..5)
+                .map(|time_step| obstacles.positions(time_step).collect_vec())
+                .collect_vec(),
+            vec![
+                vec![(0, 0), (2, 0)],
+                vec![(0, 1), (2, 1)],
+                vec![(0, 2), (2, 0)],
+                vec![(0, 1), (2, 1)],
+                vec![(0, 0), (2, 0)],
+            ]
+        );
+
+        // Neither stepping onto a guard nor passing through one is safe.
+        assert!(!obstacles.is_safe_move((1, 1), (0, 1), 0));
+        assert!(!obstacles.is_safe_move((0, 1), (0, 0), 0));
+        assert!(obstacles.is_safe_move((1, 0), (1, 1), 0));
+    }
+
+    #[test]
+    fn test_place_patrols() {
+        let config = GenerationConfig {
+            braid_factor: 0.5,
+            places_guards: true,
+            ..Default::default()
+        };
+
+        for seed in 0..10 {
+            let maze = generate_maze(
+                MazeShape::new(31, 31),
+                &config,
+                &mut ChaCha8Rng::seed_from_u64(seed),
+            );
+            let patrols = &maze.obstacles.patrols;
+            assert_eq!(patrols.len(), maze.shape.area() / AREA_PER_PATROL);
+
+            let mut occupied = Array2::from_elem((maze.shape.rows, maze.shape.cols), false);
+            for patrol in patrols {
+                assert!(patrol.period() >= 2);
+
+                for time_step in 0..patrol.period() {
+                    let (coord, next_coord) =
+                        (patrol.position(time_step), patrol.position(time_step + 1));
+                    assert_eq!(maze.cells[coord], MazeCellType::Passage);
+                    assert!(maze.shape.adjacent_coordinates(coord).contains(&next_coord));
+                }
+
+                // Routes of different guards never meet.
+                for &coord in patrol.route.iter().unique() {
+                    assert!(!occupied[coord]);
+                    occupied[coord] = true;
+                }
+            }
+        }
+    }
+}
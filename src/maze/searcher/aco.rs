@@ -0,0 +1,256 @@
+use itertools::Itertools;
+use ndarray::prelude::*;
+use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+
+use crate::maze::{calculate_manhattan_distance, MazeGrid};
+use crate::maze::{searcher::ExtraSearchError, MazeCellStatus, MazeCellType};
+
+use super::{MazeSearcher, ReservedRedraw, SearchProgress};
+
+/// Number of ants walking in one advance.
+const NUM_ANTS: usize = 20;
+
+/// Maximum number of batches of ants.
+const MAX_ITERATIONS: usize = 100;
+
+/// The search is finished if the best path has not been improved for this number of batches.
+const MAX_STALLED_ITERATIONS: usize = 20;
+
+/// Pheromone on each cell before any ant walks.
+const INIT_PHEROMONE: f64 = 0.1;
+
+/// Fraction of the pheromone evaporating after each batch of ants.
+const EVAPORATION_RATE: f64 = 0.1;
+
+/// Exponent of the pheromone in the weight of choosing the next cell.
+const PHEROMONE_WEIGHT: f64 = 1.0;
+
+/// Exponent of the closeness to the goal in the weight of choosing the next cell.
+const HEURISTIC_WEIGHT: f64 = 2.0;
+
+/// Ant colony optimization.
+///
+/// In each advance, a batch of ants walks from the start to the goal, choosing the next cell
+/// with probability weighted by the pheromone and the closeness to the goal.
+/// An ant stuck in a dead end goes back the way it came.
+/// The pheromone evaporates, and each ant deposits pheromone on its path in inverse proportion to the length.
+pub(crate) struct AntColonySearcher {
+    maze: MazeGrid,
+    cell_statuses: Array2<MazeCellStatus>,
+    progress: SearchProgress,
+
+    /// Pheromone deposited on each cell.
+    pheromones: Array2<f64>,
+
+    /// Shortest path found by the ants so far.
+    best_path: Option<Vec<(usize, usize)>>,
+
+    iteration: usize,
+    stalled_iterations: usize,
+
+    /// Seed of the random numbers, from which the search starts over when reset.
+    seed: u64,
+    rng: ChaCha8Rng,
+}
+
+impl AntColonySearcher {
+    /// Attaches a maze to be visualized, with the ants choosing their ways by random numbers from the seed.
+    pub(crate) fn new(maze: MazeGrid, seed: u64) -> Self {
+        let shape = maze.shape;
+        let cell_statuses = maze.cells.mapv(MazeCellStatus::new);
+
+        Self {
+            maze,
+            cell_statuses,
+            progress: SearchProgress::InSearch,
+            pheromones: Array2::from_elem((shape.rows, shape.cols), INIT_PHEROMONE),
+            best_path: None,
+            iteration: 0,
+            stalled_iterations: 0,
+            seed,
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        }
+    }
+
+    /// Lets an ant walk from the start and returns its path without loops if it reaches the goal.
+    fn walk_ant(&mut self) -> Option<Vec<(usize, usize)>> {
+        let MazeGrid {
+            shape, start, goal, ..
+        } = self.maze;
+
+        let mut visited = Array2::from_elem((shape.rows, shape.cols), false);
+        visited[start] = true;
+        let mut path = vec![start];
+
+        while let Some(&coord) = path.last() {
+            if coord == goal {
+                return Some(path);
+            }
+
+            let candidates = self
+                .maze
+                .passable_neighbors(coord)
+                .filter(|&adj_coord| !visited[adj_coord])
+                .collect_vec();
+
+            // Go back when stuck in a dead end.
+            if candidates.is_empty() {
+                path.pop();
+                continue;
+            }
+
+            let &next_coord = candidates
+                .choose_weighted(&mut self.rng, |&adj_coord| {
+                    let closeness =
+                        1.0 / (calculate_manhattan_distance(adj_coord, goal) + 1) as f64;
+                    self.pheromones[adj_coord].powf(PHEROMONE_WEIGHT)
+                        * closeness.powf(HEURISTIC_WEIGHT)
+                })
+                .unwrap();
+
+            visited[next_coord] = true;
+            path.push(next_coord);
+        }
+
+        None
+    }
+}
+
+impl MazeSearcher for AntColonySearcher {
+    fn maze(&self) -> &MazeGrid {
+        &self.maze
+    }
+
+    fn cell_statuses(&self) -> &Array2<MazeCellStatus> {
+        &self.cell_statuses
+    }
+
+    fn reset(&mut self) {
+        let maze = self.maze.clone();
+        *self = Self::new(maze, self.seed);
+    }
+
+    /// Lets a batch of ants walk.
+    fn advance(&mut self) -> Result<Vec<ReservedRedraw>, ExtraSearchError> {
+        let maze_shape = self.maze.shape;
+
+        match self.progress {
+            SearchProgress::InSearch => {}
+            SearchProgress::Solved => return Err(ExtraSearchError),
+            SearchProgress::NoSolution => return Err(ExtraSearchError),
+        }
+
+        let paths = (0..NUM_ANTS).filter_map(|_| self.walk_ant()).collect_vec();
+        self.iteration += 1;
+
+        // Since ants go back from dead ends, no ant reaches the goal only if there is no path.
+        let Some(shortest_path) = paths.iter().min_by_key(|path| path.len()).cloned() else {
+            self.progress = SearchProgress::NoSolution;
+            return Ok(vec![]);
+        };
+
+        // Update the pheromone.
+        self.pheromones
+            .mapv_inplace(|pheromone| pheromone * (1.0 - EVAPORATION_RATE));
+        for path in &paths {
+            let deposit = 1.0 / path.len() as f64;
+            for &coord in path {
+                self.pheromones[coord] += deposit;
+                self.cell_statuses[coord].visited = true;
+            }
+        }
+
+        // Update the best path.
+        let improved = match &self.best_path {
+            Some(best_path) => shortest_path.len() < best_path.len(),
+            None => true,
+        };
+
+        if improved {
+            if let Some(best_path) = &self.best_path {
+                for &coord in best_path {
+                    self.cell_statuses[coord].set_on_path(false);
+                }
+            }
+
+            for &coord in &shortest_path {
+                self.cell_statuses[coord].set_on_path(true);
+            }
+
+            self.best_path = Some(shortest_path);
+            self.stalled_iterations = 0;
+        } else {
+            self.stalled_iterations += 1;
+        }
+
+        // Draw the pheromone relative to the maximum as a heatmap.
+        let max_pheromone = self
+            .pheromones
+            .fold(0.0_f64, |acc, &pheromone| acc.max(pheromone));
+
+        let mut reserved_redraws = vec![];
+        for (coord, &cell_type) in self.maze.cells.indexed_iter() {
+            if cell_type != MazeCellType::Passage {
+                continue;
+            }
+
+            let heat = self.pheromones[coord] / max_pheromone;
+            self.cell_statuses[coord].set_heat(Some(heat as f32));
+
+            reserved_redraws.push(ReservedRedraw {
+                cell_idx: maze_shape.coord_to_idx(coord),
+                status: self.cell_statuses[coord],
+            });
+        }
+
+        // Update the progress.
+        if self.iteration >= MAX_ITERATIONS || self.stalled_iterations >= MAX_STALLED_ITERATIONS {
+            self.progress = SearchProgress::Solved;
+        }
+
+        Ok(reserved_redraws)
+    }
+
+    fn progress(&self) -> &SearchProgress {
+        &self.progress
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ant_colony_searcher() {
+        let maze: MazeGrid = crate::maze::TEST_MAZE.parse().unwrap();
+
+        let mut searcher = AntColonySearcher::new(maze.clone(), 12345);
+        while !searcher.terminated() {
+            searcher.advance().unwrap();
+        }
+
+        assert!(matches!(searcher.progress(), SearchProgress::Solved));
+
+        // The best path is marked from the start to the goal through passable cells.
+        let on_path = searcher
+            .cell_statuses
+            .mapv(|status| status.path_rank.is_some());
+        assert!(on_path[maze.start] && on_path[maze.goal]);
+        assert!(on_path
+            .indexed_iter()
+            .all(|(coord, &on_path)| !on_path || maze.cells[coord].is_passable()));
+        let dist_grid = maze.shape.dist_grid_from(maze.start, |from, to| {
+            maze.can_move(from, to) && on_path[to]
+        });
+        assert!(dist_grid[maze.goal].is_some());
+
+        // The same seed gives the same search.
+        let mut same_searcher = AntColonySearcher::new(maze, 12345);
+        while !same_searcher.terminated() {
+            same_searcher.advance().unwrap();
+        }
+        assert_eq!(same_searcher.best_path, searcher.best_path);
+        assert_eq!(same_searcher.pheromones, searcher.pheromones);
+    }
+}
@@ -3,13 +3,23 @@ use std::collections::BinaryHeap;
 
 use ndarray::prelude::*;
 
-use crate::maze::MazeGrid;
+use crate::maze::{calculate_manhattan_distance, MazeGrid};
 use crate::maze::{searcher::ExtraSearchError, MazeCellStatus};
 
 use super::{MazeSearcher, ReservedRedraw, SearchProgress};
 
-fn calculate_manhattan_distance(coord1: (usize, usize), coord2: (usize, usize)) -> usize {
-    coord1.0.abs_diff(coord2.0) + coord2.1.abs_diff(coord2.1)
+/// Estimates the cost from the cell to the goal by the Manhattan distance, or zero without the heuristic.
+/// The estimate never exceeds the actual cost, since entering a cell costs at least one.
+fn estimate_remaining_cost(
+    coord: (usize, usize),
+    goal: (usize, usize),
+    uses_heuristic: bool,
+) -> usize {
+    if uses_heuristic {
+        calculate_manhattan_distance(coord, goal)
+    } else {
+        0
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -28,11 +38,11 @@ impl SearchEdge {
         }
     }
 
-    fn next(self, next: (usize, usize)) -> Self {
+    fn next(self, next: (usize, usize), cost: usize) -> Self {
         SearchEdge {
             from: Some(self.to),
             to: next,
-            distance: self.distance + 1,
+            distance: self.distance + cost,
         }
     }
 }
@@ -63,12 +73,21 @@ impl Ord for WeightedEdge {
     }
 }
 
+/// Best-first search for the cheapest path, taking the costs of entering the cells into account.
+///
+/// With the heuristic, cells are expanded in the order of the cost so far plus the Manhattan distance to the goal (A*).
+/// Without it, they are expanded in the order of the cost so far (Dijkstra's algorithm).
 pub(crate) struct ASterSearcher {
     maze: MazeGrid,
     cell_statuses: Array2<MazeCellStatus>,
     edge_heap: BinaryHeap<Reverse<WeightedEdge>>,
     progress: SearchProgress,
     dist_grid: Array2<Option<usize>>,
+
+    /// Cell from which each expanded cell was entered, used to restore the path.
+    prev_coords: Array2<Option<(usize, usize)>>,
+
+    uses_heuristic: bool,
 }
 
 impl MazeSearcher for ASterSearcher {
@@ -76,6 +95,10 @@ impl MazeSearcher for ASterSearcher {
         &self.maze
     }
 
+    fn cell_statuses(&self) -> &Array2<MazeCellStatus> {
+        &self.cell_statuses
+    }
+
     fn reset(&mut self) {
         let Self {
             maze,
@@ -83,6 +106,8 @@ impl MazeSearcher for ASterSearcher {
             edge_heap,
             progress,
             dist_grid,
+            prev_coords,
+            uses_heuristic,
         } = self;
 
         *cell_statuses = maze.cells.mapv(MazeCellStatus::new);
@@ -90,12 +115,13 @@ impl MazeSearcher for ASterSearcher {
         edge_heap.clear();
         let init_weighted_edge = WeightedEdge {
             edge: SearchEdge::init(maze.start),
-            weight: calculate_manhattan_distance(maze.start, maze.goal),
+            weight: estimate_remaining_cost(maze.start, maze.goal, *uses_heuristic),
         };
         edge_heap.push(Reverse(init_weighted_edge));
 
         *progress = SearchProgress::InSearch;
         dist_grid.fill(None);
+        prev_coords.fill(None);
     }
 
     fn advance(&mut self) -> Result<Vec<ReservedRedraw>, ExtraSearchError> {
@@ -125,6 +151,7 @@ impl MazeSearcher for ASterSearcher {
         let mut reserved_redraws = vec![];
 
         self.dist_grid[edge.to] = Some(edge.distance);
+        self.prev_coords[edge.to] = edge.from;
 
         // Update visible cell components.
         if let Some(from) = edge.from {
@@ -143,24 +170,11 @@ impl MazeSearcher for ASterSearcher {
             status: self.cell_statuses[edge.to],
         });
 
-        let find_prev_coord = |coord| {
-            let dist = self.dist_grid[coord].unwrap();
-            self.maze
-                .shape
-                .adjacent_coordinates(coord)
-                .find(|&adj_coord| {
-                    self.dist_grid[adj_coord].is_some_and(|adj_dist| adj_dist == dist - 1)
-                })
-                .unwrap()
-        };
-
         // Process when the maze is solved.
         if edge.to == self.maze.goal {
             // Restore a path from the start to the goal.
             let mut path = vec![self.maze.goal];
-            path.reserve(edge.distance);
-            for _ in 0..edge.distance {
-                let prev_coord = find_prev_coord(*path.last().unwrap());
+            while let Some(prev_coord) = self.prev_coords[*path.last().unwrap()] {
                 path.push(prev_coord);
             }
             path.reverse();
@@ -183,12 +197,12 @@ impl MazeSearcher for ASterSearcher {
 
         // Update the edge stack.
         for adj_coord in self.maze.shape.adjacent_coordinates(edge.to) {
-            if self.maze.cells[adj_coord].is_passable() {
-                let weight =
-                    edge.distance + 1 + calculate_manhattan_distance(adj_coord, self.maze.goal);
+            if self.maze.can_move(edge.to, adj_coord) {
+                let adj_edge = edge.next(adj_coord, self.maze.costs[adj_coord]);
                 let adj_weighted_edge = WeightedEdge {
-                    edge: edge.next(adj_coord),
-                    weight,
+                    edge: adj_edge,
+                    weight: adj_edge.distance
+                        + estimate_remaining_cost(adj_coord, self.maze.goal, self.uses_heuristic),
                 };
                 self.edge_heap.push(Reverse(adj_weighted_edge));
             }
@@ -203,14 +217,23 @@ impl MazeSearcher for ASterSearcher {
 }
 
 impl ASterSearcher {
-    /// Attaches a maze to be visualized.
+    /// Attaches a maze to be visualized by A*.
     pub(crate) fn new(maze: MazeGrid) -> Self {
+        Self::with_heuristic(maze, true)
+    }
+
+    /// Attaches a maze to be visualized by Dijkstra's algorithm.
+    pub(crate) fn dijkstra(maze: MazeGrid) -> Self {
+        Self::with_heuristic(maze, false)
+    }
+
+    fn with_heuristic(maze: MazeGrid, uses_heuristic: bool) -> Self {
         let shape = maze.shape;
         let cell_statuses = maze.cells.mapv(MazeCellStatus::new);
 
         let init_weighted_edge = WeightedEdge {
             edge: SearchEdge::init(maze.start),
-            weight: calculate_manhattan_distance(maze.start, maze.goal),
+            weight: estimate_remaining_cost(maze.start, maze.goal, uses_heuristic),
         };
 
         Self {
@@ -219,6 +242,8 @@ impl ASterSearcher {
             edge_heap: BinaryHeap::from([Reverse(init_weighted_edge)]),
             progress: SearchProgress::InSearch,
             dist_grid: Array2::from_elem((shape.rows, shape.cols), None),
+            prev_coords: Array2::from_elem((shape.rows, shape.cols), None),
+            uses_heuristic,
         }
     }
 }
@@ -45,6 +45,10 @@ impl MazeSearcher for BFSSearcher {
         &self.maze
     }
 
+    fn cell_statuses(&self) -> &Array2<MazeCellStatus> {
+        &self.cell_statuses
+    }
+
     fn reset(&mut self) {
         let Self {
             maze,
@@ -108,8 +112,7 @@ impl MazeSearcher for BFSSearcher {
         let find_prev_coord = |coord| {
             let dist = self.dist_grid[coord].unwrap();
             self.maze
-                .shape
-                .adjacent_coordinates(coord)
+                .passable_neighbors(coord)
                 .find(|&adj_coord| {
                     self.dist_grid[adj_coord].is_some_and(|adj_dist| adj_dist == dist - 1)
                 })
@@ -145,7 +148,7 @@ impl MazeSearcher for BFSSearcher {
 
         // Update the edge stack.
         for adj_coord in self.maze.shape.adjacent_coordinates(edge.to) {
-            if self.maze.cells[adj_coord].is_passable() {
+            if self.maze.can_move(edge.to, adj_coord) {
                 self.edge_queue.push_back(edge.next(adj_coord));
             }
         }
@@ -54,6 +54,10 @@ impl MazeSearcher for DFSSearcher {
         &self.maze
     }
 
+    fn cell_statuses(&self) -> &Array2<MazeCellStatus> {
+        &self.cell_statuses
+    }
+
     fn reset(&mut self) {
         let Self {
             maze,
@@ -112,10 +116,8 @@ impl MazeSearcher for DFSSearcher {
 
             self.edge_stack.push(edge.back());
 
-            for adj_coord in self.maze.shape.adjacent_coordinates(edge.to) {
-                if self.maze.cells[adj_coord].is_passable() {
-                    self.edge_stack.push(edge.next_forward(adj_coord));
-                }
+            for adj_coord in self.maze.passable_neighbors(edge.to) {
+                self.edge_stack.push(edge.next_forward(adj_coord));
             }
         }
 
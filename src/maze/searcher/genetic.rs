@@ -0,0 +1,369 @@
+use itertools::Itertools;
+use ndarray::prelude::*;
+use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+
+use crate::maze::MazeGrid;
+use crate::maze::{searcher::ExtraSearchError, MazeCellStatus, MazeCellType};
+
+use super::{MazeSearcher, ReservedRedraw, SearchProgress};
+
+/// Number of individuals in each generation.
+const POPULATION_SIZE: usize = 100;
+
+/// Number of the best individuals copied to the next generation unchanged.
+const NUM_ELITES: usize = 5;
+
+/// Number of individuals competing to become a parent.
+const TOURNAMENT_SIZE: usize = 3;
+
+/// Probability of replacing each choice with a random one.
+const MUTATION_RATE: f64 = 0.02;
+
+/// Maximum number of generations.
+const MAX_GENERATIONS: usize = 500;
+
+/// The search is finished if the best individual reaching the goal
+/// has not been improved for this number of generations.
+const MAX_STALLED_GENERATIONS: usize = 30;
+
+/// Number of values a gene takes, divisible by any number of choices in a cell (1 to 4).
+const NUM_GENE_VALUES: usize = 12;
+
+/// Move sequence and its evaluation.
+#[derive(Debug, Clone)]
+struct Individual {
+    /// Choice of the next cell in each step.
+    genome: Vec<usize>,
+
+    /// Cells visited by following the choices from the start, until the goal is reached.
+    trajectory: Vec<(usize, usize)>,
+
+    /// Distance to the goal at the closest approach and the number of steps to get there, lower is fitter.
+    cost: (usize, usize),
+}
+
+impl Individual {
+    fn reaches(&self, goal: (usize, usize)) -> bool {
+        self.trajectory.last() == Some(&goal)
+    }
+}
+
+/// Genetic algorithm evolving a population of move sequences toward the goal.
+///
+/// Each advance is one generation.
+/// An individual is fitter if it gets closer to the goal, and then if it gets there in fewer steps.
+/// The trajectory of the best individual is highlighted,
+/// and the fraction of the population visiting each cell is drawn as a heatmap.
+pub(crate) struct GeneticSearcher {
+    maze: MazeGrid,
+    cell_statuses: Array2<MazeCellStatus>,
+    progress: SearchProgress,
+
+    /// Distance from each cell to the goal, used to evaluate the individuals.
+    dist_grid_to_goal: Array2<Option<usize>>,
+
+    /// Individuals of the current generation, the fittest first.
+    population: Vec<Individual>,
+
+    generation: usize,
+    stalled_generations: usize,
+
+    /// Seed of the random numbers, from which the evolution starts over when reset.
+    seed: u64,
+    rng: ChaCha8Rng,
+}
+
+impl GeneticSearcher {
+    /// Attaches a maze to be visualized, with the population bred by random numbers from the seed.
+    pub(crate) fn new(maze: MazeGrid, seed: u64) -> Self {
+        let cell_statuses = maze.cells.mapv(MazeCellStatus::new);
+        let dist_grid_to_goal = maze.dist_grid_from(maze.goal);
+
+        Self {
+            maze,
+            cell_statuses,
+            progress: SearchProgress::InSearch,
+            dist_grid_to_goal,
+            population: vec![],
+            generation: 0,
+            stalled_generations: 0,
+            seed,
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        }
+    }
+
+    /// Walks from the start following the choices and evaluates the trajectory.
+    ///
+    /// In each step, the walker chooses one of the adjacent cells except the one it came from,
+    /// and goes back only in a dead end.
+    fn evaluate(&self, genome: Vec<usize>) -> Individual {
+        let MazeGrid { goal, .. } = self.maze;
+
+        let mut trajectory = vec![self.maze.start];
+        for &gene in &genome {
+            let coord = trajectory[trajectory.len() - 1];
+            if coord == goal {
+                break;
+            }
+
+            let prev_coord = trajectory.len().checked_sub(2).map(|idx| trajectory[idx]);
+            let candidates = self
+                .maze
+                .passable_neighbors(coord)
+                .filter(|&adj_coord| Some(adj_coord) != prev_coord)
+                .collect_vec();
+
+            let next_coord = match candidates.len() {
+                0 => match prev_coord {
+                    Some(prev_coord) => prev_coord,
+                    None => break,
+                },
+                num_candidates => candidates[gene % num_candidates],
+            };
+            trajectory.push(next_coord);
+        }
+
+        let (steps, min_dist) = trajectory
+            .iter()
+            .enumerate()
+            .filter_map(|(step, &coord)| Some((step, self.dist_grid_to_goal[coord]?)))
+            .min_by_key(|&(step, dist)| (dist, step))
+            .unwrap_or((0, usize::MAX));
+
+        Individual {
+            genome,
+            trajectory,
+            cost: (min_dist, steps),
+        }
+    }
+
+    fn random_genome(&mut self) -> Vec<usize> {
+        // A loop-free path never visits more cells than there are passable cells.
+        let genome_len = self
+            .maze
+            .cells
+            .iter()
+            .filter(|cell_type| cell_type.is_passable())
+            .count();
+
+        (0..genome_len)
+            .map(|_| self.rng.gen_range(0..NUM_GENE_VALUES))
+            .collect()
+    }
+
+    /// Chooses the fittest of randomly chosen individuals.
+    fn select_parent(&mut self) -> &Individual {
+        (0..TOURNAMENT_SIZE)
+            .map(|_| self.rng.gen_range(0..self.population.len()))
+            .min()
+            .map(|idx| &self.population[idx])
+            .unwrap()
+    }
+
+    /// Creates a child by one-point crossover of two parents followed by mutation.
+    fn breed(&mut self) -> Vec<usize> {
+        let parent1 = self.select_parent().genome.clone();
+        let parent2 = self.select_parent().genome.clone();
+
+        let crossover_point = self.rng.gen_range(0..=parent1.len());
+        let mut child = parent1[..crossover_point].to_vec();
+        child.extend_from_slice(&parent2[crossover_point..]);
+
+        for direction in &mut child {
+            if self.rng.gen_bool(MUTATION_RATE) {
+                *direction = self.rng.gen_range(0..NUM_GENE_VALUES);
+            }
+        }
+
+        child
+    }
+
+    /// Creates the next generation, or the first one if there is no population yet.
+    fn next_generation(&mut self) -> Vec<Individual> {
+        if self.population.is_empty() {
+            return (0..POPULATION_SIZE)
+                .map(|_| {
+                    let genome = self.random_genome();
+                    self.evaluate(genome)
+                })
+                .collect();
+        }
+
+        let mut next_population = self.population[..NUM_ELITES].to_vec();
+        while next_population.len() < POPULATION_SIZE {
+            let child = self.breed();
+            next_population.push(self.evaluate(child));
+        }
+
+        next_population
+    }
+}
+
+impl MazeSearcher for GeneticSearcher {
+    fn maze(&self) -> &MazeGrid {
+        &self.maze
+    }
+
+    fn cell_statuses(&self) -> &Array2<MazeCellStatus> {
+        &self.cell_statuses
+    }
+
+    fn reset(&mut self) {
+        let maze = self.maze.clone();
+        *self = Self::new(maze, self.seed);
+    }
+
+    /// Advances the evolution by one generation.
+    fn advance(&mut self) -> Result<Vec<ReservedRedraw>, ExtraSearchError> {
+        let maze_shape = self.maze.shape;
+
+        match self.progress {
+            SearchProgress::InSearch => {}
+            SearchProgress::Solved => return Err(ExtraSearchError),
+            SearchProgress::NoSolution => return Err(ExtraSearchError),
+        }
+
+        let mut population = self.next_generation();
+        population.sort_by_key(|individual| individual.cost);
+
+        let improved = match self.population.first() {
+            Some(best_individual) => population[0].cost < best_individual.cost,
+            None => true,
+        };
+        if improved {
+            self.stalled_generations = 0;
+        } else {
+            self.stalled_generations += 1;
+        }
+
+        self.population = population;
+        self.generation += 1;
+
+        // Count the individuals visiting each cell.
+        let mut visit_counts = Array2::<usize>::zeros((maze_shape.rows, maze_shape.cols));
+        for individual in &self.population {
+            for &coord in individual.trajectory.iter().unique() {
+                visit_counts[coord] += 1;
+            }
+        }
+
+        // Overlay the trajectory of the best individual and the coverage of the population.
+        let best_individual = &self.population[0];
+        let mut reserved_redraws = vec![];
+        for (coord, &cell_type) in self.maze.cells.indexed_iter() {
+            if !cell_type.is_passable() {
+                continue;
+            }
+
+            let status = &mut self.cell_statuses[coord];
+            status.set_on_path(false);
+
+            if visit_counts[coord] > 0 {
+                status.visited = true;
+
+                if cell_type == MazeCellType::Passage {
+                    status.set_heat(Some(visit_counts[coord] as f32 / POPULATION_SIZE as f32));
+                }
+            }
+
+            reserved_redraws.push(ReservedRedraw {
+                cell_idx: maze_shape.coord_to_idx(coord),
+                status: *status,
+            });
+        }
+
+        for &coord in &best_individual.trajectory {
+            self.cell_statuses[coord].set_on_path(true);
+
+            reserved_redraws.push(ReservedRedraw {
+                cell_idx: maze_shape.coord_to_idx(coord),
+                status: self.cell_statuses[coord],
+            });
+        }
+
+        // Update the progress.
+        let solved = best_individual.reaches(self.maze.goal);
+        if solved && self.stalled_generations >= MAX_STALLED_GENERATIONS {
+            self.progress = SearchProgress::Solved;
+        } else if self.generation >= MAX_GENERATIONS {
+            self.progress = if solved {
+                SearchProgress::Solved
+            } else {
+                SearchProgress::NoSolution
+            };
+        }
+
+        Ok(reserved_redraws)
+    }
+
+    fn progress(&self) -> &SearchProgress {
+        &self.progress
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze::generate_maze::{generate_maze, GenerationConfig};
+    use crate::maze::MazeShape;
+
+    #[test]
+    fn test_evaluate() {
+        let maze: MazeGrid = crate::maze::TEST_MAZE.parse().unwrap();
+        let searcher = GeneticSearcher::new(maze, 12345);
+
+        // Turning down at the junction leads to the goal.
+        let individual = searcher.evaluate(vec![0, 0, 1, 0, 0, 0, 0]);
+        assert!(individual.reaches(searcher.maze.goal));
+        assert_eq!(individual.trajectory.len(), 7);
+        assert_eq!(individual.cost, (0, 6));
+
+        // Going straight at the junction leads to a dead end, and the junction is the closest approach.
+        let individual = searcher.evaluate(vec![0; 7]);
+        assert!(!individual.reaches(searcher.maze.goal));
+        assert_eq!(individual.cost, (4, 2));
+    }
+
+    #[test]
+    fn test_genetic_searcher() {
+        let maze = generate_maze(
+            MazeShape::new(11, 11),
+            &GenerationConfig::default(),
+            &mut ChaCha8Rng::seed_from_u64(12345),
+        );
+        let shortest_length = maze.dist_grid_from(maze.start)[maze.goal].unwrap();
+
+        let run = |maze: MazeGrid| {
+            let mut searcher = GeneticSearcher::new(maze, 12345);
+            while !searcher.terminated() {
+                searcher.advance().unwrap();
+            }
+            searcher
+        };
+        let searcher = run(maze.clone());
+
+        assert!(matches!(searcher.progress(), SearchProgress::Solved));
+
+        // The trajectory of the best individual is highlighted from the start to the goal.
+        let best_individual = &searcher.population[0];
+        assert!(best_individual.reaches(searcher.maze.goal));
+        assert!(best_individual.cost.1 >= shortest_length);
+        for &coord in &best_individual.trajectory {
+            assert!(searcher.cell_statuses[coord].path_rank.is_some());
+        }
+        assert!(searcher
+            .cell_statuses
+            .iter()
+            .filter_map(|status| status.heat)
+            .all(|heat| 0.0 < heat && heat <= 1.0));
+
+        // The same seed gives the same evolution.
+        let same_searcher = run(maze);
+        assert_eq!(same_searcher.generation, searcher.generation);
+        assert_eq!(
+            same_searcher.population[0].trajectory,
+            best_individual.trajectory
+        );
+    }
+}
@@ -0,0 +1,218 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use ndarray::prelude::*;
+
+use crate::maze::junction_graph::{extract_junction_graph, JunctionGraph};
+use crate::maze::MazeGrid;
+use crate::maze::{searcher::ExtraSearchError, MazeCellStatus};
+
+use super::{MazeSearcher, ReservedRedraw, SearchProgress};
+
+/// Node reached in the search as the cost from the start, the index of the node,
+/// and the node left for it with the index of the edge among the edges of that node (`None` for the start).
+type NodeEntry = Reverse<(usize, usize, Option<(usize, usize)>)>;
+
+/// Dijkstra's algorithm on the junction graph of the maze, expanding one junction, dead end, start or goal at a time.
+///
+/// Corridors are passed over in one step, so far fewer cells are expanded than by searching cell by cell.
+pub(crate) struct JunctionGraphSearcher {
+    maze: MazeGrid,
+    graph: JunctionGraph,
+    cell_statuses: Array2<MazeCellStatus>,
+    node_heap: BinaryHeap<NodeEntry>,
+    progress: SearchProgress,
+
+    /// Edge through which each expanded node was reached (`Some(None)` for the start), used to restore the path.
+    prev_edges: Vec<Option<Option<(usize, usize)>>>,
+
+    /// Node expanded last.
+    current_node: Option<usize>,
+}
+
+impl MazeSearcher for JunctionGraphSearcher {
+    fn maze(&self) -> &MazeGrid {
+        &self.maze
+    }
+
+    fn cell_statuses(&self) -> &Array2<MazeCellStatus> {
+        &self.cell_statuses
+    }
+
+    fn reset(&mut self) {
+        *self = Self::with_graph(self.maze.clone(), self.graph.clone());
+    }
+
+    fn advance(&mut self) -> Result<Vec<ReservedRedraw>, ExtraSearchError> {
+        let maze_shape = self.maze.shape;
+
+        match self.progress {
+            SearchProgress::InSearch => {}
+            SearchProgress::Solved => return Err(ExtraSearchError),
+            SearchProgress::NoSolution => return Err(ExtraSearchError),
+        }
+
+        let mut pop_effective_node = || {
+            while let Some(Reverse((distance, node, from))) = self.node_heap.pop() {
+                if self.prev_edges[node].is_none() {
+                    return Some((distance, node, from));
+                }
+            }
+
+            None
+        };
+
+        let Some((distance, node, from)) = pop_effective_node() else {
+            self.progress = SearchProgress::NoSolution;
+            return Ok(vec![]);
+        };
+
+        let mut reserved_redraws = vec![];
+
+        self.prev_edges[node] = Some(from);
+
+        // Update visible cell components.
+        if let Some(prev_node) = self.current_node.replace(node) {
+            let prev_coord = self.graph.nodes[prev_node];
+            self.cell_statuses[prev_coord].exit(false);
+
+            reserved_redraws.push(ReservedRedraw {
+                cell_idx: maze_shape.coord_to_idx(prev_coord),
+                status: self.cell_statuses[prev_coord],
+            });
+        }
+
+        let coord = self.graph.nodes[node];
+        self.cell_statuses[coord].enter(false);
+
+        reserved_redraws.push(ReservedRedraw {
+            cell_idx: maze_shape.coord_to_idx(coord),
+            status: self.cell_statuses[coord],
+        });
+
+        // Process when the maze is solved.
+        if coord == self.maze.goal {
+            // Restore a path from the goal to the start along the corridors.
+            let mut path = vec![coord];
+            let mut path_node = node;
+            while let Some(Some((from_node, edge_idx))) = self.prev_edges[path_node] {
+                let edge = &self.graph.edges[from_node][edge_idx];
+                path.extend(edge.corridor.iter().rev());
+                path.push(self.graph.nodes[from_node]);
+                path_node = from_node;
+            }
+
+            // Display the path from the start to the goal.
+            for &coord in path.iter().rev() {
+                self.cell_statuses[coord].set_on_path(true);
+
+                reserved_redraws.push(ReservedRedraw {
+                    cell_idx: maze_shape.coord_to_idx(coord),
+                    status: self.cell_statuses[coord],
+                });
+            }
+
+            // Update the progress.
+            self.progress = SearchProgress::Solved;
+
+            return Ok(reserved_redraws);
+        }
+
+        // Update the node heap.
+        for (edge_idx, edge) in self.graph.edges[node].iter().enumerate() {
+            if self.prev_edges[edge.to].is_none() {
+                self.node_heap.push(Reverse((
+                    distance + edge.cost,
+                    edge.to,
+                    Some((node, edge_idx)),
+                )));
+            }
+        }
+
+        Ok(reserved_redraws)
+    }
+
+    fn progress(&self) -> &SearchProgress {
+        &self.progress
+    }
+}
+
+impl JunctionGraphSearcher {
+    /// Attaches a maze to be visualized, compressing it into its junction graph.
+    pub(crate) fn new(maze: MazeGrid) -> Self {
+        let graph = extract_junction_graph(&maze);
+        Self::with_graph(maze, graph)
+    }
+
+    fn with_graph(maze: MazeGrid, graph: JunctionGraph) -> Self {
+        let cell_statuses = maze.cells.mapv(MazeCellStatus::new);
+        let start_node = graph.node_indices[maze.start].unwrap();
+
+        Self {
+            cell_statuses,
+            node_heap: BinaryHeap::from([Reverse((0, start_node, None))]),
+            progress: SearchProgress::InSearch,
+            prev_edges: vec![None; graph.nodes.len()],
+            current_node: None,
+            maze,
+            graph,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+    use crate::maze::generate_maze::{generate_maze, GenerationConfig};
+    use crate::maze::searcher::astar::ASterSearcher;
+    use crate::maze::MazeShape;
+
+    /// Runs the search to the end, and returns the cost of the path found and the number of advances.
+    fn solve(mut searcher: Box<dyn MazeSearcher>) -> (usize, usize) {
+        let mut num_advances = 0;
+        while !searcher.terminated() {
+            searcher.advance().unwrap();
+            num_advances += 1;
+        }
+        assert!(matches!(searcher.progress(), SearchProgress::Solved));
+
+        let maze = searcher.maze();
+        let path_cost = searcher
+            .cell_statuses()
+            .indexed_iter()
+            .filter(|&(coord, status)| status.path_rank.is_some() && coord != maze.start)
+            .map(|(coord, _)| maze.costs[coord])
+            .sum();
+
+        (path_cost, num_advances)
+    }
+
+    #[test]
+    fn test_junction_graph_searcher() {
+        for (algorithm, braid_factor) in
+            [("Backtracker", 0.0), ("Backtracker", 0.5), ("Terrain", 0.0)]
+        {
+            let config = GenerationConfig {
+                algorithm: algorithm.to_string(),
+                braid_factor,
+                ..Default::default()
+            };
+            let maze = generate_maze(
+                MazeShape::new(31, 31),
+                &config,
+                &mut ChaCha8Rng::seed_from_u64(12345),
+            );
+
+            let (junction_cost, junction_advances) =
+                solve(Box::new(JunctionGraphSearcher::new(maze.clone())));
+            let (dijkstra_cost, dijkstra_advances) = solve(Box::new(ASterSearcher::dijkstra(maze)));
+
+            // The same cost is found by expanding only the nodes instead of every cell.
+            assert_eq!(junction_cost, dijkstra_cost);
+            assert!(junction_advances < dijkstra_advances);
+        }
+    }
+}
@@ -1,10 +1,17 @@
 use std::{error, fmt};
 
+use ndarray::prelude::*;
+
 use super::{MazeCellStatus, MazeGrid};
 
+pub(crate) mod aco;
 pub(crate) mod astar;
 pub(crate) mod bfs;
 pub(crate) mod dfs;
+pub(crate) mod genetic;
+pub(crate) mod junction;
+pub(crate) mod qlearning;
+pub(crate) mod spacetime_astar;
 
 /// Error returned if the maze search has already been finished or interrupted,
 /// but an attempt is made to advance the search.
@@ -35,6 +42,9 @@ pub(crate) struct ReservedRedraw {
 pub(crate) trait MazeSearcher: 'static + Send + Sync {
     fn maze(&self) -> &MazeGrid;
 
+    /// Returns the current statuses of all cells of the maze.
+    fn cell_statuses(&self) -> &Array2<MazeCellStatus>;
+
     fn reset(&mut self);
 
     /// Advance the maze search by one cell.
@@ -42,6 +52,18 @@ pub(crate) trait MazeSearcher: 'static + Send + Sync {
 
     fn progress(&self) -> &SearchProgress;
 
+    /// Returns the time step shared by the searcher and the moving obstacles of the maze.
+    ///
+    /// Searchers that ignore the moving obstacles return `None`.
+    fn time_step(&self) -> Option<usize> {
+        None
+    }
+
+    /// Sets the number of training episodes run in one advance.
+    ///
+    /// Searchers that are not trained over episodes ignore it.
+    fn set_episodes_per_advance(&mut self, _episodes_per_advance: usize) {}
+
     fn terminated(&self) -> bool {
         match self.progress() {
             SearchProgress::InSearch => false,
@@ -51,7 +73,9 @@ pub(crate) trait MazeSearcher: 'static + Send + Sync {
     }
 }
 
-pub(crate) fn create_searcher<S>(maze: MazeGrid, algorithm: &S) -> Box<dyn MazeSearcher>
+/// Creates the searcher of the algorithm on the maze.
+/// Randomized searchers draw their random numbers from the seed, so that the same seed gives the same search.
+pub(crate) fn create_searcher<S>(maze: MazeGrid, algorithm: &S, seed: u64) -> Box<dyn MazeSearcher>
 where
     S: AsRef<str>,
 {
@@ -59,6 +83,12 @@ where
         "DFS" => Box::new(dfs::DFSSearcher::new(maze)),
         "BFS" => Box::new(bfs::BFSSearcher::new(maze)),
         "A*" => Box::new(astar::ASterSearcher::new(maze)),
+        "Dijkstra" => Box::new(astar::ASterSearcher::dijkstra(maze)),
+        "Junction graph" => Box::new(junction::JunctionGraphSearcher::new(maze)),
+        "Space-time A*" => Box::new(spacetime_astar::SpaceTimeAStarSearcher::new(maze)),
+        "ACO" => Box::new(aco::AntColonySearcher::new(maze, seed)),
+        "Q-learning" => Box::new(qlearning::QLearningSearcher::new(maze, seed)),
+        "GA" => Box::new(genetic::GeneticSearcher::new(maze, seed)),
         algorithm => panic!("{} is the unknown search algorithm.", algorithm),
     }
 }
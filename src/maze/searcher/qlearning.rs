@@ -0,0 +1,293 @@
+use ndarray::prelude::*;
+use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+
+use crate::maze::{searcher::ExtraSearchError, MazeCellStatus, MazeCellType};
+use crate::maze::{MazeGrid, ADJACENT_DISPLACEMENT};
+
+use super::{MazeSearcher, ReservedRedraw, SearchProgress};
+
+/// Maximum number of training episodes.
+const MAX_EPISODES: usize = 5000;
+
+/// The training is finished if the greedy path has reached the goal
+/// without changing for this number of advances.
+const MAX_STABLE_ADVANCES: usize = 10;
+
+/// Weight of a new estimate when updating the action values.
+const LEARNING_RATE: f64 = 0.5;
+
+/// Discount factor of future rewards.
+const DISCOUNT_FACTOR: f64 = 0.99;
+
+/// Probability of taking a random action instead of the greedy one.
+const EXPLORATION_RATE: f64 = 0.2;
+
+/// Reward for each move (including bumping into a wall).
+const STEP_REWARD: f64 = -1.0;
+
+/// Tabular Q-learning agent trained over episodes from the start to the goal.
+///
+/// Each advance runs the set number of episodes.
+/// The greedy action of each cell is drawn as an arrow and the value as a heatmap,
+/// and the path following the greedy actions from the start is highlighted.
+pub(crate) struct QLearningSearcher {
+    maze: MazeGrid,
+    cell_statuses: Array2<MazeCellStatus>,
+    progress: SearchProgress,
+
+    /// Estimated value of each action (an index of `ADJACENT_DISPLACEMENT`) in each cell.
+    action_values: Array3<f64>,
+
+    /// Path following the greedy actions from the start.
+    greedy_path: Vec<(usize, usize)>,
+
+    episodes: usize,
+    episodes_per_advance: usize,
+    stable_advances: usize,
+
+    /// Seed of the random numbers, from which the training starts over when reset.
+    seed: u64,
+    rng: ChaCha8Rng,
+}
+
+impl QLearningSearcher {
+    /// Attaches a maze to be visualized, with the agent exploring by random numbers from the seed.
+    pub(crate) fn new(maze: MazeGrid, seed: u64) -> Self {
+        let shape = maze.shape;
+        let cell_statuses = maze.cells.mapv(MazeCellStatus::new);
+
+        Self {
+            maze,
+            cell_statuses,
+            progress: SearchProgress::InSearch,
+            action_values: Array3::zeros((shape.rows, shape.cols, ADJACENT_DISPLACEMENT.len())),
+            greedy_path: vec![],
+            episodes: 0,
+            episodes_per_advance: 1,
+            stable_advances: 0,
+            seed,
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        }
+    }
+
+    /// Returns the cell reached by taking the action in the cell.
+    /// If the action leads into a wall or out of the maze, the agent stays in the cell.
+    fn take_action(&self, coord: (usize, usize), action: usize) -> (usize, usize) {
+        let (diff_row, diff_col) = ADJACENT_DISPLACEMENT[action];
+        let next_coord = (
+            coord.0.wrapping_add(diff_row),
+            coord.1.wrapping_add(diff_col),
+        );
+
+        if self.maze.shape.in_range(next_coord) && self.maze.can_move(coord, next_coord) {
+            next_coord
+        } else {
+            coord
+        }
+    }
+
+    /// Returns the action with the highest value in the cell and the value.
+    fn greedy_action(&self, coord: (usize, usize)) -> (usize, f64) {
+        (0..ADJACENT_DISPLACEMENT.len())
+            .map(|action| (action, self.action_values[(coord.0, coord.1, action)]))
+            .max_by(|(_, value1), (_, value2)| value1.total_cmp(value2))
+            .unwrap()
+    }
+
+    /// Runs an episode from the start until the goal is reached or the steps run out.
+    fn run_episode(&mut self) {
+        let max_steps = 4 * self.maze.shape.area();
+
+        let mut coord = self.maze.start;
+        for _ in 0..max_steps {
+            if coord == self.maze.goal {
+                break;
+            }
+
+            let action = if self.rng.gen_bool(EXPLORATION_RATE) {
+                self.rng.gen_range(0..ADJACENT_DISPLACEMENT.len())
+            } else {
+                self.greedy_action(coord).0
+            };
+
+            let next_coord = self.take_action(coord, action);
+
+            // The value of the goal is zero since the episode ends there.
+            let next_value = if next_coord == self.maze.goal {
+                0.0
+            } else {
+                self.greedy_action(next_coord).1
+            };
+
+            let action_value = &mut self.action_values[(coord.0, coord.1, action)];
+            *action_value +=
+                LEARNING_RATE * (STEP_REWARD + DISCOUNT_FACTOR * next_value - *action_value);
+
+            self.cell_statuses[coord].visited = true;
+            coord = next_coord;
+        }
+    }
+
+    /// Follows the greedy actions from the start until the goal or an already visited cell is reached.
+    fn find_greedy_path(&self) -> Vec<(usize, usize)> {
+        let mut path = vec![self.maze.start];
+
+        while let Some(&coord) = path.last() {
+            if coord == self.maze.goal {
+                break;
+            }
+
+            let next_coord = self.take_action(coord, self.greedy_action(coord).0);
+            if path.contains(&next_coord) {
+                break;
+            }
+
+            path.push(next_coord);
+        }
+
+        path
+    }
+}
+
+impl MazeSearcher for QLearningSearcher {
+    fn maze(&self) -> &MazeGrid {
+        &self.maze
+    }
+
+    fn cell_statuses(&self) -> &Array2<MazeCellStatus> {
+        &self.cell_statuses
+    }
+
+    fn reset(&mut self) {
+        let episodes_per_advance = self.episodes_per_advance;
+        *self = Self::new(self.maze.clone(), self.seed);
+        self.episodes_per_advance = episodes_per_advance;
+    }
+
+    /// Runs a batch of training episodes.
+    fn advance(&mut self) -> Result<Vec<ReservedRedraw>, ExtraSearchError> {
+        let maze_shape = self.maze.shape;
+
+        match self.progress {
+            SearchProgress::InSearch => {}
+            SearchProgress::Solved => return Err(ExtraSearchError),
+            SearchProgress::NoSolution => return Err(ExtraSearchError),
+        }
+
+        for _ in 0..self.episodes_per_advance {
+            self.run_episode();
+        }
+        self.episodes += self.episodes_per_advance;
+
+        // Update the greedy path.
+        let greedy_path = self.find_greedy_path();
+
+        if greedy_path == self.greedy_path && greedy_path.last() == Some(&self.maze.goal) {
+            self.stable_advances += 1;
+        } else {
+            self.stable_advances = 0;
+        }
+
+        for &coord in &self.greedy_path {
+            self.cell_statuses[coord].set_on_path(false);
+        }
+        for &coord in &greedy_path {
+            self.cell_statuses[coord].set_on_path(true);
+        }
+        self.greedy_path = greedy_path;
+
+        // Draw the greedy actions as arrows and the values relative to the lowest one as a heatmap.
+        let min_value = self
+            .maze
+            .cells
+            .indexed_iter()
+            .filter(|&(coord, _)| self.cell_statuses[coord].visited)
+            .map(|(coord, _)| self.greedy_action(coord).1)
+            .fold(0.0_f64, f64::min);
+
+        let mut reserved_redraws = vec![];
+        for (coord, &cell_type) in self.maze.cells.indexed_iter() {
+            if !cell_type.is_passable() {
+                continue;
+            }
+
+            if self.cell_statuses[coord].visited {
+                let (action, value) = self.greedy_action(coord);
+                self.cell_statuses[coord].set_direction(Some(action));
+
+                if cell_type == MazeCellType::Passage && min_value < 0.0 {
+                    self.cell_statuses[coord].set_heat(Some((1.0 - value / min_value) as f32));
+                }
+            }
+
+            reserved_redraws.push(ReservedRedraw {
+                cell_idx: maze_shape.coord_to_idx(coord),
+                status: self.cell_statuses[coord],
+            });
+        }
+
+        // Update the progress.
+        if self.stable_advances >= MAX_STABLE_ADVANCES {
+            self.progress = SearchProgress::Solved;
+        } else if self.episodes >= MAX_EPISODES {
+            self.progress = if self.greedy_path.last() == Some(&self.maze.goal) {
+                SearchProgress::Solved
+            } else {
+                SearchProgress::NoSolution
+            };
+        }
+
+        Ok(reserved_redraws)
+    }
+
+    fn progress(&self) -> &SearchProgress {
+        &self.progress
+    }
+
+    fn set_episodes_per_advance(&mut self, episodes_per_advance: usize) {
+        self.episodes_per_advance = episodes_per_advance.max(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use super::*;
+
+    #[test]
+    fn test_qlearning_searcher() {
+        let maze: MazeGrid = crate::maze::TEST_MAZE.parse().unwrap();
+        let shortest_length = maze.dist_grid_from(maze.start)[maze.goal].unwrap();
+
+        let mut searcher = QLearningSearcher::new(maze.clone(), 12345);
+        searcher.set_episodes_per_advance(20);
+        while !searcher.terminated() {
+            searcher.advance().unwrap();
+        }
+
+        assert!(matches!(searcher.progress(), SearchProgress::Solved));
+
+        // The greedy policy walks from the start to the goal by the shortest way.
+        let greedy_path = searcher.find_greedy_path();
+        assert_eq!(greedy_path.first(), Some(&maze.start));
+        assert_eq!(greedy_path.last(), Some(&maze.goal));
+        assert!(greedy_path
+            .iter()
+            .tuple_windows()
+            .all(|(&from, &to)| maze.passable_neighbors(from).contains(&to)));
+        assert_eq!(greedy_path.len(), shortest_length + 1);
+
+        // The arrows and the values are drawn on passable cells only.
+        for (coord, status) in searcher.cell_statuses.indexed_iter() {
+            if status.direction.is_some() {
+                assert!(maze.cells[coord].is_passable());
+            }
+            if status.heat.is_some() {
+                assert_eq!(maze.cells[coord], MazeCellType::Passage);
+            }
+        }
+        assert!(searcher.cell_statuses[maze.start].direction.is_some());
+    }
+}
@@ -0,0 +1,395 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::iter;
+
+use ndarray::prelude::*;
+
+use crate::maze::MazeGrid;
+use crate::maze::{searcher::ExtraSearchError, MazeCellStatus};
+
+use super::{MazeSearcher, ReservedRedraw, SearchProgress};
+
+/// Pair of a cell and the time step at which the agent stays in the cell.
+type SpaceTimeNode = ((usize, usize), usize);
+
+#[derive(Debug, Clone, Copy)]
+struct SearchEdge {
+    from: Option<SpaceTimeNode>,
+    to: SpaceTimeNode,
+}
+
+impl SearchEdge {
+    fn init(start: (usize, usize)) -> Self {
+        Self {
+            from: None,
+            to: (start, 0),
+        }
+    }
+
+    fn next(self, next: (usize, usize)) -> Self {
+        SearchEdge {
+            from: Some(self.to),
+            to: (next, self.to.1 + 1),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct WeightedEdge {
+    edge: SearchEdge,
+    weight: usize,
+}
+
+impl PartialEq for WeightedEdge {
+    fn eq(&self, other: &Self) -> bool {
+        self.weight == other.weight
+    }
+}
+
+impl Eq for WeightedEdge {}
+
+impl PartialOrd for WeightedEdge {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for WeightedEdge {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.weight.cmp(&other.weight)
+    }
+}
+
+/// Phase of the search with moving obstacles.
+#[derive(Debug, Clone)]
+enum SearchPhase {
+    /// Expanding nodes of the time-expanded graph.
+    Planning,
+
+    /// Moving the agent along the planned path in lockstep with the guards.
+    Replaying {
+        path: Vec<SpaceTimeNode>,
+        step: usize,
+    },
+}
+
+/// A* search over pairs of a cell and a time step, avoiding the guards patrolling the maze.
+/// The agent may also wait in its current cell.
+/// The distance to the goal ignoring the guards is used as the heuristic.
+///
+/// Once a path to the goal is found, the agent walks along it one time step per advance.
+pub(crate) struct SpaceTimeAStarSearcher {
+    maze: MazeGrid,
+    cell_statuses: Array2<MazeCellStatus>,
+    edge_heap: BinaryHeap<Reverse<WeightedEdge>>,
+    progress: SearchProgress,
+    phase: SearchPhase,
+
+    /// Previous node of each expanded node.
+    /// Since the guards move periodically, time steps are stored modulo the period.
+    prev_nodes: HashMap<SpaceTimeNode, Option<SpaceTimeNode>>,
+
+    /// Number of time steps after which the guards are back at their initial positions.
+    period: usize,
+
+    /// Distance from each cell to the goal, ignoring the guards.
+    ///
+    /// The distance is used as the heuristic of the search, since it is never more than the number
+    /// of time steps actually needed to reach the goal.
+    dist_grid_to_goal: Array2<Option<usize>>,
+
+    time_step: usize,
+}
+
+impl SpaceTimeAStarSearcher {
+    /// Attaches a maze to be visualized.
+    pub(crate) fn new(maze: MazeGrid) -> Self {
+        let cell_statuses = maze.cells.mapv(MazeCellStatus::new);
+        let period = maze.obstacles.period();
+        let dist_grid_to_goal = maze.dist_grid_from(maze.goal);
+
+        // If the goal cannot be reached even without the guards, there is nothing to search.
+        let edge_heap = match dist_grid_to_goal[maze.start] {
+            Some(dist) => BinaryHeap::from([Reverse(WeightedEdge {
+                edge: SearchEdge::init(maze.start),
+                weight: dist,
+            })]),
+            None => BinaryHeap::new(),
+        };
+
+        Self {
+            maze,
+            cell_statuses,
+            edge_heap,
+            progress: SearchProgress::InSearch,
+            phase: SearchPhase::Planning,
+            prev_nodes: HashMap::new(),
+            period,
+            dist_grid_to_goal,
+            time_step: 0,
+        }
+    }
+
+    /// Expands one node of the time-expanded graph.
+    fn advance_planning(&mut self) -> Vec<ReservedRedraw> {
+        let maze_shape = self.maze.shape;
+        let period = self.period;
+
+        let mut pop_effective_edge = || {
+            while let Some(Reverse(WeightedEdge { edge, weight: _ })) = self.edge_heap.pop() {
+                let (coord, time_step) = edge.to;
+                if !self.prev_nodes.contains_key(&(coord, time_step % period)) {
+                    return Some(edge);
+                }
+            }
+
+            None
+        };
+
+        let Some(edge) = pop_effective_edge() else {
+            self.progress = SearchProgress::NoSolution;
+            return vec![];
+        };
+
+        let (coord, time_step) = edge.to;
+        self.prev_nodes
+            .insert((coord, time_step % period), edge.from);
+        self.time_step = time_step;
+
+        let mut reserved_redraws = vec![];
+
+        // Update visible cell components.
+        if let Some((from, _)) = edge.from {
+            self.cell_statuses[from].exit(false);
+
+            reserved_redraws.push(ReservedRedraw {
+                cell_idx: maze_shape.coord_to_idx(from),
+                status: self.cell_statuses[from],
+            });
+        }
+
+        self.cell_statuses[coord].enter(false);
+
+        reserved_redraws.push(ReservedRedraw {
+            cell_idx: maze_shape.coord_to_idx(coord),
+            status: self.cell_statuses[coord],
+        });
+
+        // Process when a path to the goal is found.
+        if coord == self.maze.goal {
+            // Restore a path from the start to the goal.
+            let node_key = |(coord, time_step): SpaceTimeNode| (coord, time_step % period);
+            let mut path = vec![edge.to];
+            while let Some(prev_node) = self.prev_nodes[&node_key(*path.last().unwrap())] {
+                path.push(prev_node);
+            }
+            path.reverse();
+
+            // The agent starts over from the start.
+            self.cell_statuses[coord].exit(false);
+
+            reserved_redraws.push(ReservedRedraw {
+                cell_idx: maze_shape.coord_to_idx(coord),
+                status: self.cell_statuses[coord],
+            });
+
+            self.phase = SearchPhase::Replaying { path, step: 0 };
+
+            return reserved_redraws;
+        }
+
+        // Update the edge heap.
+        // Waiting in the current cell is also a move.
+        for next_coord in maze_shape
+            .adjacent_coordinates(coord)
+            .filter(|&adj_coord| self.maze.can_move(coord, adj_coord))
+            .chain(iter::once(coord))
+        {
+            let Some(dist_to_goal) = self.dist_grid_to_goal[next_coord] else {
+                continue;
+            };
+
+            if self
+                .maze
+                .obstacles
+                .is_safe_move(coord, next_coord, time_step)
+                && !self
+                    .prev_nodes
+                    .contains_key(&(next_coord, (time_step + 1) % period))
+            {
+                let weight = time_step + 1 + dist_to_goal;
+                let next_weighted_edge = WeightedEdge {
+                    edge: edge.next(next_coord),
+                    weight,
+                };
+                self.edge_heap.push(Reverse(next_weighted_edge));
+            }
+        }
+
+        reserved_redraws
+    }
+
+    /// Moves the agent one time step along the planned path.
+    fn advance_replaying(&mut self) -> Vec<ReservedRedraw> {
+        let maze_shape = self.maze.shape;
+
+        let SearchPhase::Replaying { path, step } = &mut self.phase else {
+            panic!("The path to be replayed has not been planned yet.");
+        };
+
+        let mut reserved_redraws = vec![];
+
+        let (coord, time_step) = path[*step];
+
+        // Update visible cell components.
+        if *step > 0 {
+            let (prev_coord, _) = path[*step - 1];
+
+            if prev_coord != coord {
+                self.cell_statuses[prev_coord].exit(false);
+
+                reserved_redraws.push(ReservedRedraw {
+                    cell_idx: maze_shape.coord_to_idx(prev_coord),
+                    status: self.cell_statuses[prev_coord],
+                });
+            }
+        }
+
+        self.cell_statuses[coord].enter(false);
+        self.cell_statuses[coord].set_on_path(true);
+
+        reserved_redraws.push(ReservedRedraw {
+            cell_idx: maze_shape.coord_to_idx(coord),
+            status: self.cell_statuses[coord],
+        });
+
+        self.time_step = time_step;
+
+        // Update the progress.
+        *step += 1;
+        if *step == path.len() {
+            self.progress = SearchProgress::Solved;
+        }
+
+        reserved_redraws
+    }
+}
+
+impl MazeSearcher for SpaceTimeAStarSearcher {
+    fn maze(&self) -> &MazeGrid {
+        &self.maze
+    }
+
+    fn cell_statuses(&self) -> &Array2<MazeCellStatus> {
+        &self.cell_statuses
+    }
+
+    fn reset(&mut self) {
+        let maze = self.maze.clone();
+        *self = Self::new(maze);
+    }
+
+    fn advance(&mut self) -> Result<Vec<ReservedRedraw>, ExtraSearchError> {
+        match self.progress {
+            SearchProgress::InSearch => {}
+            SearchProgress::Solved => return Err(ExtraSearchError),
+            SearchProgress::NoSolution => return Err(ExtraSearchError),
+        }
+
+        match self.phase {
+            SearchPhase::Planning => Ok(self.advance_planning()),
+            SearchPhase::Replaying { .. } => Ok(self.advance_replaying()),
+        }
+    }
+
+    fn progress(&self) -> &SearchProgress {
+        &self.progress
+    }
+
+    fn time_step(&self) -> Option<usize> {
+        Some(self.time_step)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+    use crate::maze::generate_maze::{generate_maze, GenerationConfig};
+    use crate::maze::obstacle::{MovingObstacles, Patrol};
+    use crate::maze::MazeShape;
+
+    /// Runs the planning phase, and returns the planned path.
+    fn plan(searcher: &mut SpaceTimeAStarSearcher) -> Vec<SpaceTimeNode> {
+        while matches!(searcher.phase, SearchPhase::Planning) {
+            searcher.advance().unwrap();
+        }
+
+        let SearchPhase::Replaying { path, .. } = &searcher.phase else {
+            unreachable!();
+        };
+        path.clone()
+    }
+
+    #[test]
+    fn test_wait_for_guard() {
+        let mut maze: MazeGrid = "S . . . G\n# # . # #\n# # . # #".parse().unwrap();
+        maze.obstacles =
+            MovingObstacles::new(vec![Patrol::back_and_forth(vec![(2, 2), (1, 2), (0, 2)])]);
+
+        // The guard comes up to the corridor at the time step 2, so the agent waits once to let it go.
+        let path = plan(&mut SpaceTimeAStarSearcher::new(maze));
+        assert_eq!(path.first(), Some(&((0, 0), 0)));
+        assert_eq!(path.last(), Some(&((0, 4), 5)));
+    }
+
+    #[test]
+    fn test_collision_free_plan() {
+        let config = GenerationConfig {
+            braid_factor: 0.5,
+            places_guards: true,
+            ..Default::default()
+        };
+
+        for seed in 0..10 {
+            let maze = generate_maze(
+                MazeShape::new(31, 31),
+                &config,
+                &mut ChaCha8Rng::seed_from_u64(seed),
+            );
+            assert!(!maze.obstacles.is_empty());
+
+            let mut searcher = SpaceTimeAStarSearcher::new(maze.clone());
+            let path = plan(&mut searcher);
+            assert_eq!(path.first(), Some(&(maze.start, 0)));
+            assert_eq!(path.last().unwrap().0, maze.goal);
+
+            // Each move steps to an adjacent cell or waits, without running into a guard.
+            for (&(coord, time_step), &(next_coord, next_time_step)) in path.iter().tuple_windows()
+            {
+                assert_eq!(next_time_step, time_step + 1);
+                assert!(
+                    coord == next_coord
+                        || maze.shape.adjacent_coordinates(coord).contains(&next_coord)
+                );
+                assert!(maze.obstacles.is_safe_move(coord, next_coord, time_step));
+            }
+
+            // The agent walks along the path with the guards in lockstep.
+            while !searcher.terminated() {
+                searcher.advance().unwrap();
+                let time_step = searcher.time_step().unwrap();
+                assert!(searcher.cell_statuses[path[time_step].0].stay);
+                assert!(!maze
+                    .obstacles
+                    .positions(time_step)
+                    .contains(&path[time_step].0));
+            }
+            assert!(matches!(searcher.progress(), SearchProgress::Solved));
+            assert_eq!(searcher.time_step(), Some(path.len() - 1));
+        }
+    }
+}
@@ -0,0 +1,159 @@
+use itertools::Itertools;
+use ndarray::prelude::*;
+use num_bigint::BigUint;
+use num_traits::{One, ToPrimitive, Zero};
+
+use super::MazeGrid;
+
+/// Number of shortest paths from the start to the goal.
+#[derive(Debug, Clone)]
+pub(crate) struct ShortestPathCount {
+    /// Cost of the shortest paths, which is their length on a maze without terrain.
+    pub(crate) cost: usize,
+
+    /// Number of distinct shortest paths.
+    pub(crate) total: BigUint,
+
+    /// Number of shortest paths passing through each cell.
+    pub(crate) through_cells: Array2<BigUint>,
+}
+
+impl ShortestPathCount {
+    /// Returns the fraction of the shortest paths passing through the cell.
+    pub(crate) fn ratio_through(&self, coord: (usize, usize)) -> f64 {
+        // Drop the lower bits so that both numbers fit in `f64`.
+        let shift = self
+            .total
+            .bits()
+            .saturating_sub(f64::MANTISSA_DIGITS as u64);
+        let through = (&self.through_cells[coord] >> shift).to_f64().unwrap();
+        let total = (&self.total >> shift).to_f64().unwrap();
+
+        through / total
+    }
+}
+
+/// Counts the cheapest paths from the start to the goal, which are the shortest ones on a maze without terrain.
+///
+/// The paths are counted on the costs from the start found by Dijkstra's algorithm,
+/// going through the cells in order of the cost.
+pub(crate) fn count_shortest_paths(maze: &MazeGrid) -> Option<ShortestPathCount> {
+    count_shortest_paths_on_costs(maze, &maze.cost_grid_from(maze.start))
+}
+
+/// Counts the cheapest paths from the start to the goal using the costs from the start.
+///
+/// Cheapest paths are exactly those walks that enter each cell at its lowest cost from the start.
+fn count_shortest_paths_on_costs(
+    maze: &MazeGrid,
+    cost_grid: &Array2<Option<usize>>,
+) -> Option<ShortestPathCount> {
+    let shape = maze.shape;
+    let cost = cost_grid[maze.goal]?;
+
+    // Cells not costing more than the goal, in order of the cost from the start.
+    // Every cell costs at least one to enter, so a move on a cheapest path always goes later in the order.
+    let ordered_coords = cost_grid
+        .indexed_iter()
+        .filter_map(|(coord, &cell_cost)| Some((cell_cost.filter(|&c| c <= cost)?, coord)))
+        .sorted()
+        .map(|(_, coord)| coord)
+        .collect_vec();
+
+    // Whether a cheapest path from the start may move from `coord` to `next_coord`.
+    let is_cheapest_move = |coord: (usize, usize), next_coord: (usize, usize)| {
+        maze.can_move(coord, next_coord)
+            && matches!(
+                (cost_grid[coord], cost_grid[next_coord]),
+                (Some(cost), Some(next_cost)) if cost + maze.costs[next_coord] == next_cost
+            )
+    };
+
+    // Number of cheapest paths from the start to each cell.
+    let mut counts_from_start = Array2::from_elem((shape.rows, shape.cols), BigUint::zero());
+    counts_from_start[maze.start] = BigUint::one();
+    for &coord in ordered_coords.iter().skip(1) {
+        counts_from_start[coord] = shape
+            .adjacent_coordinates(coord)
+            .filter(|&prev_coord| is_cheapest_move(prev_coord, coord))
+            .map(|prev_coord| &counts_from_start[prev_coord])
+            .sum();
+    }
+
+    // Number of cheapest paths from each cell to the goal.
+    let mut counts_to_goal = Array2::from_elem((shape.rows, shape.cols), BigUint::zero());
+    counts_to_goal[maze.goal] = BigUint::one();
+    for &coord in ordered_coords.iter().rev() {
+        if coord == maze.goal {
+            continue;
+        }
+
+        counts_to_goal[coord] = shape
+            .adjacent_coordinates(coord)
+            .filter(|&next_coord| is_cheapest_move(coord, next_coord))
+            .map(|next_coord| &counts_to_goal[next_coord])
+            .sum();
+    }
+
+    let through_cells = Array2::from_shape_vec(
+        (shape.rows, shape.cols),
+        counts_from_start
+            .iter()
+            .zip(&counts_to_goal)
+            .map(|(count_from_start, count_to_goal)| count_from_start * count_to_goal)
+            .collect_vec(),
+    )
+    .unwrap();
+
+    Some(ShortestPathCount {
+        cost,
+        total: counts_from_start[maze.goal].clone(),
+        through_cells,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count_shortest_paths() {
+        let maze: MazeGrid = "S . . .\n. . . .\n. . G .".parse().unwrap();
+
+        let count = count_shortest_paths(&maze).unwrap();
+
+        assert_eq!(count.cost, 4);
+        assert_eq!(count.total, BigUint::from(6_u32));
+        assert_eq!(count.through_cells[(1, 1)], BigUint::from(4_u32));
+        assert_eq!(count.through_cells[(0, 3)], BigUint::zero());
+        assert_eq!(count.ratio_through(maze.goal), 1.0);
+    }
+
+    #[test]
+    fn test_count_shortest_paths_with_terrain() {
+        let mut maze: MazeGrid = "S . . .\n. . . .\n. . G .".parse().unwrap();
+        maze.costs[(1, 1)] = 3;
+
+        let count = count_shortest_paths(&maze).unwrap();
+
+        // Only the two paths passing above and below the costly cell remain cheapest.
+        assert_eq!(count.cost, 4);
+        assert_eq!(count.total, BigUint::from(2_u32));
+        assert_eq!(count.through_cells[(1, 1)], BigUint::zero());
+        assert_eq!(count.through_cells[(0, 2)], BigUint::one());
+    }
+
+    #[test]
+    fn test_count_shortest_paths_overflowing_u128() {
+        let mut maze_str = vec![". ".repeat(100).trim_end().to_string(); 100];
+        maze_str[0].replace_range(0..1, "S");
+        maze_str[99].replace_range(198..199, "G");
+        let maze: MazeGrid = maze_str.join("\n").parse().unwrap();
+
+        let count = count_shortest_paths(&maze).unwrap();
+
+        // The binomial coefficient C(198, 99) has 59 decimal digits.
+        assert_eq!(count.total.to_string().len(), 59);
+        assert!((count.ratio_through(maze.start) - 1.0).abs() < 1e-9);
+    }
+}
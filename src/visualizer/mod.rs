@@ -1,16 +1,30 @@
+use std::iter;
 use std::rc::Rc;
 use std::sync::{mpsc, Arc, Mutex};
 use std::thread;
 use std::time::Duration;
 
 use itertools::Itertools;
+use ndarray::Array2;
+use num_traits::Zero;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
 
-use crate::maze::generate_maze::generate_maze;
-use crate::maze::searcher::{create_searcher, MazeSearcher};
-use crate::maze::MazeGrid;
+use crate::application::AppConfig;
+use crate::maze::analysis::{analyze_maze, measure_difficulty, DifficultyTarget};
+use crate::maze::generate_maze::endpoints::{move_endpoints, EndpointStrategy};
+use crate::maze::generate_maze::growing_tree::CellChoicePolicy;
+use crate::maze::generate_maze::{
+    create_generator, GenerationConfig, MazeGenerator, StreamedGeneration,
+};
+use crate::maze::junction_graph::extract_junction_graph;
+use crate::maze::k_shortest_paths::find_k_shortest_paths;
+use crate::maze::searcher::{create_searcher, MazeSearcher, SearchProgress};
+use crate::maze::shortest_path_count::count_shortest_paths;
+use crate::maze::{MazeCellStatus, MazeCellType, MazeGrid, MazeShape, ADJACENT_DISPLACEMENT};
 use crate::{MainWindow, MazeCellProperty};
 
-/// Signal to the thread that performs the automatic search of the maze.
+/// Signal to the thread that performs the automatic generation or search of the maze.
 enum TaskSignal {
     /// Signal to interrupt the automatic search.
     Interrupt,
@@ -18,13 +32,50 @@ enum TaskSignal {
 
 #[derive(Debug)]
 struct AutoSearchTask {
-    /// Handle of the thread performing automatic maze search.
+    /// Handle of the thread performing automatic maze generation or search.
     handle: thread::JoinHandle<()>,
 
     /// Sender of the signal to the thread.
     sender: mpsc::Sender<TaskSignal>,
 }
 
+/// Interval between the advances of the automatic search.
+const SEARCH_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Interval between the advances of the animated generation, shorter than that of the search
+/// so that the generation does not keep the search waiting long.
+const GENERATION_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Maze generation being animated, with the settings of the searcher attached to the generated maze.
+struct AnimatedGeneration {
+    generator: Box<dyn MazeGenerator>,
+
+    /// Seed from which the maze is generated, which the searcher also draws its random numbers from.
+    seed: u64,
+
+    /// Name of the search algorithm.
+    search_algorithm: String,
+
+    /// Number of training episodes run in one advance of the search.
+    episodes_per_advance: usize,
+
+    /// Difficulty which the maze is generated to fall in, if any.
+    difficulty_target: Option<DifficultyTarget>,
+}
+
+/// The k shortest loop-free paths from the start to the goal, highlighted one at a time.
+#[derive(Debug)]
+struct PathCycler {
+    /// Number of requested paths.
+    k: usize,
+
+    /// Found paths in order of length.
+    paths: Vec<Vec<(usize, usize)>>,
+
+    /// Index of the highlighted path.
+    highlighted_idx: Option<usize>,
+}
+
 /// If automatic search is begin performed, interrupt and wait for the thread to finish.
 fn interrupt_search(task: Arc<Mutex<Option<AutoSearchTask>>>) {
     if let Some(AutoSearchTask { handle, sender }) = task.lock().unwrap().take() {
@@ -34,24 +85,163 @@ fn interrupt_search(task: Arc<Mutex<Option<AutoSearchTask>>>) {
 }
 
 /// Draws the maze in an unexplored state.
+///
+/// The costs of entering the passages are shaded relative to the highest one,
+/// and the walls on the edges between the cells are drawn for a maze with thin walls.
 fn initialize_maze_drawing(
     maze: &MazeGrid,
     handle_weak: slint::Weak<MainWindow>,
 ) -> Result<(), slint::EventLoopError> {
-    let properties = maze
+    let max_cost = maze
         .cells
         .iter()
-        .cloned()
-        .map(MazeCellProperty::init)
+        .zip(&maze.costs)
+        .filter(|(cell_type, _)| cell_type.is_passable())
+        .map(|(_, &cost)| cost)
+        .max()
+        .unwrap_or(1);
+
+    let properties = maze
+        .cells
+        .indexed_iter()
+        .zip(&maze.costs)
+        .map(|((coord, &cell_type), &cost)| {
+            let mut property = MazeCellProperty::init(cell_type);
+            if cell_type == MazeCellType::Passage && max_cost > 1 {
+                property.terrain = (cost - 1) as f32 / (max_cost - 1) as f32;
+            }
+            if let Some(edge_walls) = &maze.edge_walls {
+                property.walls = MazeCellProperty::encode_walls(edge_walls.sides(coord));
+            }
+
+            property
+        })
         .collect_vec();
 
+    let maze = maze.clone();
     handle_weak.upgrade_in_event_loop(move |handle| {
         let model = Rc::new(slint::VecModel::from(properties));
         handle.set_properties_of_cells(model.into());
+
+        if handle.get_shows_junction_graph() {
+            draw_junction_graph(&maze, &handle);
+        }
+    })
+}
+
+/// Draws the junction graph of the maze over the cells,
+/// with the nodes as circles, the corridors as lines through the cells and the cost of each corridor at its middle.
+///
+/// The cost drawn is that of entering the cells of the corridor, without the nodes at its ends,
+/// so that it is the same in both directions.
+fn draw_junction_graph(maze: &MazeGrid, handle: &MainWindow) {
+    let shape = maze.shape;
+    let graph = extract_junction_graph(maze);
+
+    let mut links = Array2::zeros((shape.rows, shape.cols));
+    let mut weights = Array2::zeros((shape.rows, shape.cols));
+
+    // Index of the direction from the cell to the adjacent cell.
+    let direction = |from: (usize, usize), to: (usize, usize)| {
+        ADJACENT_DISPLACEMENT
+            .iter()
+            .position(|&(diff_row, diff_col)| {
+                (from.0.wrapping_add(diff_row), from.1.wrapping_add(diff_col)) == to
+            })
+            .unwrap()
+    };
+
+    for (node_idx, edges) in graph.edges.iter().enumerate() {
+        // Each corridor is an edge from both of its ends, and is drawn from the end with the smaller index.
+        for edge in edges.iter().filter(|edge| node_idx <= edge.to) {
+            let cells = iter::once(graph.nodes[node_idx])
+                .chain(edge.corridor.iter().copied())
+                .chain(iter::once(graph.nodes[edge.to]));
+
+            for (from, to) in cells.tuple_windows() {
+                links[from] |= 1 << direction(from, to);
+                links[to] |= 1 << direction(to, from);
+            }
+
+            if let Some(&middle) = edge.corridor.get(edge.corridor.len() / 2) {
+                let corridor_cost: usize =
+                    edge.corridor.iter().map(|&coord| maze.costs[coord]).sum();
+                weights[middle] = corridor_cost as i32;
+            }
+        }
+    }
+
+    for (coord, &cell_links) in links.indexed_iter() {
+        handle.set_cell_graph(
+            shape.coord_to_idx(coord),
+            graph.node_indices[coord].is_some(),
+            cell_links,
+            weights[coord],
+        );
+    }
+}
+
+/// Clears the junction graph drawn over the cells.
+fn clear_junction_graph(shape: MazeShape, handle: &MainWindow) {
+    for cell_idx in 0..shape.area() {
+        handle.set_cell_graph(cell_idx, false, 0, 0);
+    }
+}
+
+/// Draws a maze filled with walls, on which the generation is animated.
+///
+/// Every cell is walled in on all sides, which is drawn only for a maze with thin walls.
+fn initialize_generation_drawing(
+    shape: MazeShape,
+    handle_weak: slint::Weak<MainWindow>,
+) -> Result<(), slint::EventLoopError> {
+    let properties = (0..shape.area())
+        .map(|_| MazeCellProperty {
+            walls: MazeCellProperty::encode_walls([true; 4]),
+            ..MazeCellProperty::init(MazeCellType::Wall)
+        })
+        .collect_vec();
+
+    handle_weak.upgrade_in_event_loop(move |handle| {
+        let model = Rc::new(slint::VecModel::from(properties));
+        handle.set_properties_of_cells(model.into());
+    })
+}
+
+/// Moves the guards drawn on the maze from their positions at `prev_time_step` to those at `time_step`.
+/// Guards are not drawn at the time step of `None`.
+fn redraw_obstacles(
+    maze: &MazeGrid,
+    prev_time_step: Option<usize>,
+    time_step: Option<usize>,
+    handle_weak: slint::Weak<MainWindow>,
+) -> Result<(), slint::EventLoopError> {
+    let collect_cell_indices = |time_step: Option<usize>| match time_step {
+        Some(time_step) => maze
+            .obstacles
+            .positions(time_step)
+            .map(|coord| maze.shape.coord_to_idx(coord))
+            .collect_vec(),
+        None => vec![],
+    };
+
+    let prev_cell_indices = collect_cell_indices(prev_time_step);
+    let cell_indices = collect_cell_indices(time_step);
+
+    handle_weak.upgrade_in_event_loop(move |handle| {
+        for cell_idx in prev_cell_indices {
+            handle.set_cell_occupied(cell_idx, false);
+        }
+
+        for cell_idx in cell_indices {
+            handle.set_cell_occupied(cell_idx, true);
+        }
     })
 }
 
 /// Advance the maze search by one cell and reflect it in the drawing.
+///
+/// The guards of the maze are moved to the time step reached by the searcher.
 fn advance_search(
     searcher: Arc<Mutex<Box<dyn MazeSearcher>>>,
     handle_weak: slint::Weak<MainWindow>,
@@ -60,6 +250,7 @@ fn advance_search(
         return false;
     }
 
+    let prev_time_step = searcher.lock().unwrap().time_step();
     let updated_statuses = searcher.lock().unwrap().advance().unwrap();
 
     for updated_status in updated_statuses {
@@ -70,15 +261,96 @@ fn advance_search(
             .unwrap();
     }
 
+    let searcher = searcher.lock().unwrap();
+    redraw_obstacles(
+        searcher.maze(),
+        prev_time_step,
+        searcher.time_step(),
+        handle_weak,
+    )
+    .unwrap();
+
     true
 }
 
-/// Automatically search the maze.
+/// Advance the maze generation in progress by one step and reflect it in the drawing.
+///
+/// When the generation is finished, the searcher is replaced with one on the generated maze,
+/// and the maze is drawn ready for the search.
+/// Returns `false` if no generation is in progress.
+fn advance_generation(
+    generator: Arc<Mutex<Option<AnimatedGeneration>>>,
+    searcher: Arc<Mutex<Box<dyn MazeSearcher>>>,
+    handle_weak: slint::Weak<MainWindow>,
+) -> bool {
+    let mut generator = generator.lock().unwrap();
+    let Some(current_generation) = generator.as_mut() else {
+        return false;
+    };
+
+    let redraws = current_generation.generator.advance().unwrap();
+
+    for redraw in redraws {
+        handle_weak
+            .upgrade_in_event_loop(move |handle| {
+                handle.redraw_cell(redraw.cell_idx, MazeCellStatus::new(redraw.cell_type));
+                if let Some(walls) = redraw.walls {
+                    handle.set_cell_walls(redraw.cell_idx, walls);
+                }
+            })
+            .unwrap();
+    }
+
+    if current_generation.generator.terminated() {
+        let AnimatedGeneration {
+            generator: finished_generator,
+            seed,
+            search_algorithm,
+            episodes_per_advance,
+            difficulty_target,
+        } = generator.take().unwrap();
+        let maze = finished_generator.finish();
+
+        let mut new_searcher = create_searcher(maze.clone(), &search_algorithm, seed);
+        new_searcher.set_episodes_per_advance(episodes_per_advance);
+        initialize_maze_drawing(&maze, handle_weak.clone()).unwrap();
+        redraw_obstacles(&maze, None, new_searcher.time_step(), handle_weak.clone()).unwrap();
+        *searcher.lock().unwrap() = new_searcher;
+
+        handle_weak
+            .upgrade_in_event_loop(move |handle| {
+                show_maze_analysis(&maze, difficulty_target.as_ref(), &handle);
+            })
+            .unwrap();
+    }
+
+    true
+}
+
+/// Skips the rest of the generation in progress, if any, and returns the generated maze with its analysis shown.
+/// A search for a maze in the target difficulty is stopped at the closest maze so far instead of being waited for.
+fn finish_generation(
+    generator: &Mutex<Option<AnimatedGeneration>>,
+    handle: &MainWindow,
+) -> Option<MazeGrid> {
+    let generation = generator.lock().unwrap().take()?;
+    let maze = generation.generator.finish();
+    show_maze_analysis(&maze, generation.difficulty_target.as_ref(), handle);
+
+    Some(maze)
+}
+
+/// Automatically generate or search the maze.
+///
+/// If a generation is in progress, it is animated to the end without starting the search.
 fn auto_search_maze(
     searcher: Arc<Mutex<Box<dyn MazeSearcher>>>,
+    generator: Arc<Mutex<Option<AnimatedGeneration>>>,
     receiver: mpsc::Receiver<TaskSignal>,
     handle_weak: slint::Weak<MainWindow>,
 ) {
+    let generating = generator.lock().unwrap().is_some();
+
     loop {
         if let Ok(signal) = receiver.try_recv() {
             match signal {
@@ -86,40 +358,397 @@ fn auto_search_maze(
             }
         }
 
-        if !advance_search(Arc::clone(&searcher), handle_weak.clone()) {
+        let advanced = if generating {
+            advance_generation(
+                Arc::clone(&generator),
+                Arc::clone(&searcher),
+                handle_weak.clone(),
+            )
+        } else {
+            advance_search(Arc::clone(&searcher), handle_weak.clone())
+        };
+
+        if !advanced {
             break;
         }
 
-        thread::sleep(Duration::from_millis(100));
+        thread::sleep(if generating {
+            GENERATION_INTERVAL
+        } else {
+            SEARCH_INTERVAL
+        });
     }
 }
 
 /// Spawns an automatic search task.
 fn spawn_auto_search_task(
     searcher: Arc<Mutex<Box<dyn MazeSearcher>>>,
+    generator: Arc<Mutex<Option<AnimatedGeneration>>>,
     handle_weak: slint::Weak<MainWindow>,
 ) -> AutoSearchTask {
     let (sender, receiver) = mpsc::channel();
-    let handle = thread::spawn(move || auto_search_maze(searcher, receiver, handle_weak));
+    let handle =
+        thread::spawn(move || auto_search_maze(searcher, generator, receiver, handle_weak));
 
     AutoSearchTask { handle, sender }
 }
 
+/// Replaces the highlighted path with the next of the k shortest paths,
+/// or with the path found by the searcher after the last of them.
+///
+/// The paths are searched for when first requested or when the number of requested paths changes.
+fn highlight_next_path(
+    searcher: &dyn MazeSearcher,
+    path_cycler: &mut Option<PathCycler>,
+    k: usize,
+    handle: &MainWindow,
+) {
+    let maze = searcher.maze();
+
+    let redraw_path = |path: &[(usize, usize)], path_rank: Option<usize>| {
+        for &coord in path {
+            let mut status = searcher.cell_statuses()[coord];
+            status.set_path_rank(path_rank);
+            handle.redraw_cell(maze.shape.coord_to_idx(coord), status);
+        }
+    };
+
+    // Remove the highlight of the path found by the searcher and the previously highlighted path.
+    let searched_path = searcher
+        .cell_statuses()
+        .indexed_iter()
+        .filter(|(_, status)| status.path_rank.is_some())
+        .map(|(coord, _)| coord)
+        .collect_vec();
+    redraw_path(&searched_path, None);
+
+    let restore_searched_path = || {
+        for &coord in &searched_path {
+            let status = searcher.cell_statuses()[coord];
+            handle.redraw_cell(maze.shape.coord_to_idx(coord), status);
+        }
+    };
+
+    if let Some(PathCycler {
+        paths,
+        highlighted_idx: Some(highlighted_idx),
+        ..
+    }) = path_cycler
+    {
+        redraw_path(&paths[*highlighted_idx], None);
+    }
+
+    let needs_search = match path_cycler {
+        Some(path_cycler) => path_cycler.k != k,
+        None => true,
+    };
+
+    if needs_search {
+        *path_cycler = Some(PathCycler {
+            k,
+            paths: find_k_shortest_paths(maze, k),
+            highlighted_idx: None,
+        });
+    }
+
+    let PathCycler {
+        paths,
+        highlighted_idx,
+        ..
+    } = path_cycler.as_mut().unwrap();
+
+    if paths.is_empty() {
+        restore_searched_path();
+        handle.set_path_info("No path found.".into());
+        return;
+    }
+
+    // After the last path, the path found by the searcher is shown again before cycling from the first.
+    let next_idx = match *highlighted_idx {
+        None => 0,
+        Some(idx) if idx + 1 < paths.len() => idx + 1,
+        Some(_) => {
+            *highlighted_idx = None;
+            restore_searched_path();
+            handle.set_path_info("Path found by the search".into());
+            return;
+        }
+    };
+    *highlighted_idx = Some(next_idx);
+    redraw_path(&paths[next_idx], Some(next_idx));
+
+    let path = &paths[next_idx];
+    let mut info = format!(
+        "Path {}/{} (length {}",
+        next_idx + 1,
+        paths.len(),
+        path.len() - 1
+    );
+    if maze.has_terrain() {
+        info.push_str(&format!(", cost {}", maze.path_cost(path)));
+    }
+    info.push(')');
+    handle.set_path_info(info.into());
+}
+
+/// Shades each passage by the fraction of the shortest paths from the start to the goal passing through it.
+fn shade_shortest_paths(searcher: &dyn MazeSearcher, handle: &MainWindow) {
+    let maze = searcher.maze();
+
+    let Some(count) = count_shortest_paths(maze) else {
+        handle.set_path_info("No path found.".into());
+        return;
+    };
+
+    for (coord, count_through) in count.through_cells.indexed_iter() {
+        if count_through.is_zero() || maze.cells[coord] != MazeCellType::Passage {
+            continue;
+        }
+
+        let mut status = searcher.cell_statuses()[coord];
+        status.set_heat(Some(count.ratio_through(coord) as f32));
+        handle.redraw_cell(maze.shape.coord_to_idx(coord), status);
+    }
+
+    let (kind, measure) = if maze.has_terrain() {
+        ("cheapest", "cost")
+    } else {
+        ("shortest", "length")
+    };
+    handle.set_path_info(
+        format!(
+            "{} {} paths ({} {})",
+            count.total, kind, measure, count.cost
+        )
+        .into(),
+    );
+}
+
+/// Shows the statistics and the difficulty metrics of the maze,
+/// and whether the metrics fall in the target if any.
+fn show_maze_analysis(maze: &MazeGrid, target: Option<&DifficultyTarget>, handle: &MainWindow) {
+    handle.set_maze_statistics(analyze_maze(maze).to_string().into());
+
+    let Some(metrics) = measure_difficulty(maze) else {
+        handle.set_difficulty_info("No path from the start to the goal.".into());
+        return;
+    };
+
+    let mut info = format!(
+        "Path {:.0}%, {} decisions, {} dead ends, {} DFS visits",
+        metrics.solution_length_ratio * 100.0,
+        metrics.decision_points,
+        metrics.dead_ends,
+        metrics.dfs_expansions,
+    );
+    if target.is_some_and(|target| !target.contains(&metrics)) {
+        info.push_str(" (out of range)");
+    }
+
+    handle.set_difficulty_info(info.into());
+}
+
+/// Discards the k shortest paths found for the previous search.
+fn clear_path_cycler(path_cycler: &Mutex<Option<PathCycler>>, handle: &MainWindow) {
+    *path_cycler.lock().unwrap() = None;
+    handle.set_path_info("".into());
+}
+
+/// Creates a searcher of the algorithm selected in the menu, drawing its random numbers from the seed.
+fn create_selected_searcher(
+    maze: MazeGrid,
+    seed: u64,
+    handle: &MainWindow,
+) -> Box<dyn MazeSearcher> {
+    let mut searcher = create_searcher(maze, &handle.get_selected_search_algorithm(), seed);
+    searcher.set_episodes_per_advance(handle.get_episodes_per_step() as usize);
+    searcher
+}
+
+/// Replaces the searcher with a new one on the maze generated from the seed,
+/// skipping the rest of the generation in progress.
 fn update_maze_searcher(
     maze: MazeGrid,
+    seed: u64,
     searcher: Arc<Mutex<Box<dyn MazeSearcher>>>,
+    generator: Arc<Mutex<Option<AnimatedGeneration>>>,
     task: Arc<Mutex<Option<AutoSearchTask>>>,
+    path_cycler: Arc<Mutex<Option<PathCycler>>>,
     handle_weak: slint::Weak<MainWindow>,
 ) {
     interrupt_search(task);
+    *generator.lock().unwrap() = None;
+    clear_path_cycler(&path_cycler, &handle_weak.unwrap());
     initialize_maze_drawing(&maze, handle_weak.clone()).unwrap();
-    *searcher.lock().unwrap() =
-        create_searcher(maze, &handle_weak.unwrap().get_selected_search_algorithm());
+
+    let new_searcher = create_selected_searcher(maze, seed, &handle_weak.unwrap());
+    redraw_obstacles(
+        new_searcher.maze(),
+        None,
+        new_searcher.time_step(),
+        handle_weak,
+    )
+    .unwrap();
+    *searcher.lock().unwrap() = new_searcher;
+}
+
+/// Starts animating the generation of a new maze.
+/// A searcher of the algorithm in the settings is attached to the maze when the generation is finished.
+fn start_maze_generation(
+    generation: AnimatedGeneration,
+    searcher: Arc<Mutex<Box<dyn MazeSearcher>>>,
+    generator: Arc<Mutex<Option<AnimatedGeneration>>>,
+    task: Arc<Mutex<Option<AutoSearchTask>>>,
+    path_cycler: Arc<Mutex<Option<PathCycler>>>,
+    handle_weak: slint::Weak<MainWindow>,
+) {
+    let handle = handle_weak.unwrap();
+
+    interrupt_search(Arc::clone(&task));
+    clear_path_cycler(&path_cycler, &handle);
+    initialize_generation_drawing(generation.generator.shape(), handle_weak.clone()).unwrap();
+    handle.set_maze_statistics("".into());
+    handle.set_difficulty_info("Generating the maze...".into());
+
+    *generator.lock().unwrap() = Some(generation);
+
+    *task.lock().unwrap() = Some(spawn_auto_search_task(searcher, generator, handle_weak));
+}
+
+/// Reads the size and the style of the next maze from the menu,
+/// and updates the size of the drawn maze and the cells.
+///
+/// In the thin-wall style, the size is the number of rooms, which may be even.
+/// Otherwise, even numbers are rounded up to odd ones.
+/// Only mazes following the lattice are drawn in the thin-wall style.
+fn update_maze_size(
+    app_config: &Mutex<AppConfig>,
+    follows_lattice: bool,
+    handle: &MainWindow,
+) -> MazeShape {
+    let rows = handle.get_new_maze_rows().max(1) as usize;
+    let cols = handle.get_new_maze_cols().max(1) as usize;
+
+    let mut app_config = app_config.lock().unwrap();
+    app_config.thin_walls = handle.get_new_maze_thin_walls() && follows_lattice;
+
+    if app_config.thin_walls {
+        app_config.maze_rows = rows;
+        app_config.maze_cols = cols;
+    } else {
+        // The generators need odd numbers of rows and columns.
+        app_config.maze_rows = rows | 1;
+        app_config.maze_cols = cols | 1;
+
+        handle.set_new_maze_rows(app_config.maze_rows as i32);
+        handle.set_new_maze_cols(app_config.maze_cols as i32);
+    }
+
+    handle.resize_maze(&app_config);
+
+    app_config.maze_shape()
+}
+
+/// Starts animating the generation of a new maze from the seed with the size and settings in the menu,
+/// and shows the seed in the menu.
+fn start_seeded_generation(
+    seed: u64,
+    app_config: &Mutex<AppConfig>,
+    searcher: Arc<Mutex<Box<dyn MazeSearcher>>>,
+    generator: Arc<Mutex<Option<AnimatedGeneration>>>,
+    task: Arc<Mutex<Option<AutoSearchTask>>>,
+    path_cycler: Arc<Mutex<Option<PathCycler>>>,
+    handle_weak: slint::Weak<MainWindow>,
+) {
+    let handle = handle_weak.unwrap();
+    handle.set_seed(seed.to_string().into());
+
+    let current_endpoints = {
+        let searcher = searcher.lock().unwrap();
+        (searcher.maze().start, searcher.maze().goal)
+    };
+    let config = read_generation_config(&handle, current_endpoints);
+    let follows_lattice = create_generator(&config).follows_lattice();
+    let maze_shape = update_maze_size(app_config, follows_lattice, &handle);
+    let difficulty_target = config.difficulty_target.clone();
+    let generation = StreamedGeneration::new(maze_shape, config, ChaCha8Rng::seed_from_u64(seed));
+    start_maze_generation(
+        AnimatedGeneration {
+            generator: Box::new(generation),
+            seed,
+            search_algorithm: handle.get_selected_search_algorithm().to_string(),
+            episodes_per_advance: handle.get_episodes_per_step() as usize,
+            difficulty_target,
+        },
+        searcher,
+        generator,
+        task,
+        path_cycler,
+        handle_weak,
+    );
+}
+
+/// Reads the settings of the maze generation from the menu.
+/// The manual placement keeps the start and the goal at `current_endpoints`.
+fn read_generation_config(
+    handle: &MainWindow,
+    current_endpoints: ((usize, usize), (usize, usize)),
+) -> GenerationConfig {
+    GenerationConfig {
+        algorithm: handle.get_selected_generation_algorithm().to_string(),
+        cell_choice_policy: CellChoicePolicy::new(
+            &handle.get_cell_choice_policy(),
+            handle.get_newest_ratio() as f64,
+        ),
+        braid_factor: handle.get_braid_factor() as f64,
+        obstacle_density: handle.get_obstacle_density() as f64,
+        guarantees_path: handle.get_guarantees_path(),
+        endpoint_strategy: EndpointStrategy::new(
+            &handle.get_endpoint_strategy(),
+            handle.get_endpoint_distance() as usize,
+            current_endpoints,
+        ),
+        places_guards: handle.get_places_guards(),
+        thin_walls: handle.get_new_maze_thin_walls(),
+        difficulty_target: handle
+            .get_targets_difficulty()
+            .then(|| read_difficulty_target(handle)),
+    }
+}
+
+/// Reads the ranges of the difficulty metrics from the menu.
+fn read_difficulty_target(handle: &MainWindow) -> DifficultyTarget {
+    let range = |min: i32, max: i32| min.max(0) as usize..=max.max(0) as usize;
+
+    DifficultyTarget {
+        solution_length_ratio: handle.get_min_path_ratio() as f64 / 100.0
+            ..=handle.get_max_path_ratio() as f64 / 100.0,
+        decision_points: range(
+            handle.get_min_decision_points(),
+            handle.get_max_decision_points(),
+        ),
+        dead_ends: range(handle.get_min_dead_ends(), handle.get_max_dead_ends()),
+        dfs_expansions: range(
+            handle.get_min_dfs_expansions(),
+            handle.get_max_dfs_expansions(),
+        ),
+    }
 }
 
 pub(crate) struct Visualizer {
     searcher: Arc<Mutex<Box<dyn MazeSearcher>>>,
+
+    /// Generation of the next maze being animated, if any.
+    generator: Arc<Mutex<Option<AnimatedGeneration>>>,
+
     task: Arc<Mutex<Option<AutoSearchTask>>>,
+    path_cycler: Arc<Mutex<Option<PathCycler>>>,
+
+    /// Seed from which the current maze was generated.
+    seed: Arc<Mutex<u64>>,
+
+    /// Settings of the window, including the size of the current maze.
+    app_config: Arc<Mutex<AppConfig>>,
 }
 
 impl Visualizer {
@@ -127,13 +756,22 @@ impl Visualizer {
     fn set_play_pause_callback(&self, handle_weak: slint::Weak<MainWindow>) {
         let task = Arc::clone(&self.task);
         let searcher = Arc::clone(&self.searcher);
+        let generator = Arc::clone(&self.generator);
 
         handle_weak.unwrap().on_play_pause_callback(move || {
-            if task.lock().unwrap().is_some() {
-                interrupt_search(task.clone());
-            } else {
+            let running = task
+                .lock()
+                .unwrap()
+                .as_ref()
+                .is_some_and(|task| !task.handle.is_finished());
+
+            // A task that has finished by itself, such as the animation of the generation, is cleaned up.
+            interrupt_search(task.clone());
+
+            if !running {
                 *task.lock().unwrap() = Some(spawn_auto_search_task(
                     searcher.clone(),
+                    generator.clone(),
                     handle_weak.clone(),
                 ));
             }
@@ -144,38 +782,139 @@ impl Visualizer {
     fn set_advance_callback(&self, handle_weak: slint::Weak<MainWindow>) {
         let task = Arc::clone(&self.task);
         let searcher = Arc::clone(&self.searcher);
+        let generator = Arc::clone(&self.generator);
 
         handle_weak.unwrap().on_advance_callback(move || {
             interrupt_search(task.clone());
 
-            advance_search(searcher.clone(), handle_weak.clone());
+            if !advance_generation(generator.clone(), searcher.clone(), handle_weak.clone()) {
+                advance_search(searcher.clone(), handle_weak.clone());
+            }
         });
     }
 
     /// Sets the process when the reset button is pressed.
+    ///
+    /// The rest of the generation in progress is skipped.
     fn set_reset_callback(&self, handle_weak: slint::Weak<MainWindow>) {
         let task = Arc::clone(&self.task);
         let searcher = Arc::clone(&self.searcher);
+        let generator = Arc::clone(&self.generator);
+        let path_cycler = Arc::clone(&self.path_cycler);
+        let seed = Arc::clone(&self.seed);
 
         handle_weak.unwrap().on_reset_callback(move || {
             interrupt_search(task.clone());
 
-            searcher.lock().unwrap().reset();
-            initialize_maze_drawing(searcher.lock().unwrap().maze(), handle_weak.clone()).unwrap();
+            if let Some(maze) = finish_generation(&generator, &handle_weak.unwrap()) {
+                update_maze_searcher(
+                    maze,
+                    *seed.lock().unwrap(),
+                    Arc::clone(&searcher),
+                    Arc::clone(&generator),
+                    Arc::clone(&task),
+                    Arc::clone(&path_cycler),
+                    handle_weak.clone(),
+                );
+                return;
+            }
+
+            clear_path_cycler(&path_cycler, &handle_weak.unwrap());
+
+            let mut searcher = searcher.lock().unwrap();
+            searcher.reset();
+            initialize_maze_drawing(searcher.maze(), handle_weak.clone()).unwrap();
+            redraw_obstacles(
+                searcher.maze(),
+                None,
+                searcher.time_step(),
+                handle_weak.clone(),
+            )
+            .unwrap();
         });
     }
 
     /// Sets the process when the change button is pressed.
+    ///
+    /// The new maze is generated from a random seed.
     fn set_change_callback(&self, handle_weak: slint::Weak<MainWindow>) {
         let task = Arc::clone(&self.task);
         let searcher = Arc::clone(&self.searcher);
-        let maze_shape = self.searcher.lock().unwrap().maze().shape;
+        let generator = Arc::clone(&self.generator);
+        let path_cycler = Arc::clone(&self.path_cycler);
+        let seed = Arc::clone(&self.seed);
+        let app_config = Arc::clone(&self.app_config);
 
         handle_weak.unwrap().on_change_callback(move || {
-            update_maze_searcher(
-                generate_maze(maze_shape, &mut rand::thread_rng()),
+            let new_seed = rand::random();
+            *seed.lock().unwrap() = new_seed;
+
+            start_seeded_generation(
+                new_seed,
+                &app_config,
                 Arc::clone(&searcher),
+                Arc::clone(&generator),
                 Arc::clone(&task),
+                Arc::clone(&path_cycler),
+                handle_weak.clone(),
+            );
+        });
+    }
+
+    /// Sets the process when a generation setting is changed.
+    ///
+    /// The maze is generated again from the current seed, so that only the changed setting makes a difference.
+    fn set_settings_callback(&self, handle_weak: slint::Weak<MainWindow>) {
+        let task = Arc::clone(&self.task);
+        let searcher = Arc::clone(&self.searcher);
+        let generator = Arc::clone(&self.generator);
+        let path_cycler = Arc::clone(&self.path_cycler);
+        let seed = Arc::clone(&self.seed);
+        let app_config = Arc::clone(&self.app_config);
+
+        handle_weak.unwrap().on_settings_callback(move || {
+            let current_seed = *seed.lock().unwrap();
+
+            start_seeded_generation(
+                current_seed,
+                &app_config,
+                Arc::clone(&searcher),
+                Arc::clone(&generator),
+                Arc::clone(&task),
+                Arc::clone(&path_cycler),
+                handle_weak.clone(),
+            );
+        });
+    }
+
+    /// Sets the process when a seed is entered.
+    ///
+    /// The same seed with the same settings and size always gives the same maze.
+    fn set_seed_callback(&self, handle_weak: slint::Weak<MainWindow>) {
+        let task = Arc::clone(&self.task);
+        let searcher = Arc::clone(&self.searcher);
+        let generator = Arc::clone(&self.generator);
+        let path_cycler = Arc::clone(&self.path_cycler);
+        let seed = Arc::clone(&self.seed);
+        let app_config = Arc::clone(&self.app_config);
+
+        handle_weak.unwrap().on_seed_callback(move || {
+            let handle = handle_weak.unwrap();
+
+            let Ok(new_seed) = handle.get_seed().trim().parse() else {
+                // Show the seed of the current maze again.
+                handle.set_seed(seed.lock().unwrap().to_string().into());
+                return;
+            };
+            *seed.lock().unwrap() = new_seed;
+
+            start_seeded_generation(
+                new_seed,
+                &app_config,
+                Arc::clone(&searcher),
+                Arc::clone(&generator),
+                Arc::clone(&task),
+                Arc::clone(&path_cycler),
                 handle_weak.clone(),
             );
         });
@@ -184,34 +923,209 @@ impl Visualizer {
     fn set_select_algorithm_callback(&self, handle_weak: slint::Weak<MainWindow>) {
         let task = Arc::clone(&self.task);
         let searcher = Arc::clone(&self.searcher);
+        let generator = Arc::clone(&self.generator);
+        let path_cycler = Arc::clone(&self.path_cycler);
+        let seed = Arc::clone(&self.seed);
 
         handle_weak.unwrap().on_select_algorithm_callback(move || {
-            let maze = searcher.lock().unwrap().maze().clone();
+            let maze = finish_generation(&generator, &handle_weak.unwrap())
+                .unwrap_or_else(|| searcher.lock().unwrap().maze().clone());
             update_maze_searcher(
                 maze,
+                *seed.lock().unwrap(),
                 Arc::clone(&searcher),
+                Arc::clone(&generator),
                 Arc::clone(&task),
+                Arc::clone(&path_cycler),
                 handle_weak.clone(),
             );
         });
     }
 
+    /// Sets the process when the number of episodes per step is edited.
+    fn set_episodes_per_step_callback(&self, handle_weak: slint::Weak<MainWindow>) {
+        let searcher = Arc::clone(&self.searcher);
+        let generator = Arc::clone(&self.generator);
+
+        handle_weak.unwrap().on_episodes_per_step_callback(move || {
+            let episodes_per_step = handle_weak.unwrap().get_episodes_per_step() as usize;
+
+            if let Some(generation) = generator.lock().unwrap().as_mut() {
+                generation.episodes_per_advance = episodes_per_step;
+            }
+            searcher
+                .lock()
+                .unwrap()
+                .set_episodes_per_advance(episodes_per_step);
+        });
+    }
+
+    /// Sets the process when the next path button is pressed.
+    fn set_next_path_callback(&self, handle_weak: slint::Weak<MainWindow>) {
+        let searcher = Arc::clone(&self.searcher);
+        let generator = Arc::clone(&self.generator);
+        let path_cycler = Arc::clone(&self.path_cycler);
+
+        handle_weak.unwrap().on_next_path_callback(move || {
+            let handle = handle_weak.unwrap();
+
+            if generator.lock().unwrap().is_some() {
+                handle.set_path_info("Wait for the maze to be generated.".into());
+                return;
+            }
+
+            let searcher = searcher.lock().unwrap();
+
+            // Paths are listed only after the maze is solved.
+            if !matches!(searcher.progress(), SearchProgress::Solved) {
+                handle.set_path_info("Solve the maze first.".into());
+                return;
+            }
+
+            let k = handle.get_path_count().max(1) as usize;
+            highlight_next_path(
+                searcher.as_ref(),
+                &mut path_cycler.lock().unwrap(),
+                k,
+                &handle,
+            );
+        });
+    }
+
+    /// Sets the process when the count paths button is pressed.
+    fn set_count_paths_callback(&self, handle_weak: slint::Weak<MainWindow>) {
+        let task = Arc::clone(&self.task);
+        let searcher = Arc::clone(&self.searcher);
+        let generator = Arc::clone(&self.generator);
+
+        handle_weak.unwrap().on_count_paths_callback(move || {
+            interrupt_search(task.clone());
+
+            if generator.lock().unwrap().is_some() {
+                handle_weak
+                    .unwrap()
+                    .set_path_info("Wait for the maze to be generated.".into());
+                return;
+            }
+
+            let searcher = searcher.lock().unwrap();
+            shade_shortest_paths(searcher.as_ref(), &handle_weak.unwrap());
+        });
+    }
+
+    /// Sets the process when the junction graph check box is toggled.
+    fn set_junction_graph_callback(&self, handle_weak: slint::Weak<MainWindow>) {
+        let searcher = Arc::clone(&self.searcher);
+        let generator = Arc::clone(&self.generator);
+
+        handle_weak.unwrap().on_junction_graph_callback(move || {
+            // The graph is drawn when the generation is finished.
+            if generator.lock().unwrap().is_some() {
+                return;
+            }
+
+            let handle = handle_weak.unwrap();
+            let searcher = searcher.lock().unwrap();
+            if handle.get_shows_junction_graph() {
+                draw_junction_graph(searcher.maze(), &handle);
+            } else {
+                clear_junction_graph(searcher.maze().shape, &handle);
+            }
+        });
+    }
+
+    /// Sets the process when a cell of the maze is pressed.
+    ///
+    /// With the manual placement, the primary button moves the start and the secondary button moves the goal.
+    fn set_cell_pressed_callback(&self, handle_weak: slint::Weak<MainWindow>) {
+        let task = Arc::clone(&self.task);
+        let searcher = Arc::clone(&self.searcher);
+        let generator = Arc::clone(&self.generator);
+        let path_cycler = Arc::clone(&self.path_cycler);
+        let seed = Arc::clone(&self.seed);
+
+        handle_weak
+            .unwrap()
+            .on_cell_pressed_callback(move |cell_idx, moves_goal| {
+                if handle_weak.unwrap().get_endpoint_strategy() != "Manual"
+                    || generator.lock().unwrap().is_some()
+                {
+                    return;
+                }
+
+                let maze = searcher.lock().unwrap().maze().clone();
+                let coord = maze.shape.idx_to_coord(cell_idx as usize);
+                let (start, goal) = if moves_goal {
+                    (maze.start, coord)
+                } else {
+                    (coord, maze.goal)
+                };
+
+                // The guards are placed again from the seed, so that the same endpoints give the same guards.
+                let seed = *seed.lock().unwrap();
+                let mut rng = ChaCha8Rng::seed_from_u64(seed);
+                let Some(moved_maze) = move_endpoints(&maze, start, goal, &mut rng) else {
+                    return;
+                };
+                show_maze_analysis(&moved_maze, None, &handle_weak.unwrap());
+
+                update_maze_searcher(
+                    moved_maze,
+                    seed,
+                    Arc::clone(&searcher),
+                    Arc::clone(&generator),
+                    Arc::clone(&task),
+                    Arc::clone(&path_cycler),
+                    handle_weak.clone(),
+                );
+            });
+    }
+
+    /// Attaches the searcher of the maze generated from the seed.
     pub(crate) fn new(
         searcher: Box<dyn MazeSearcher>,
+        seed: u64,
+        app_config: AppConfig,
         handle_weak: slint::Weak<MainWindow>,
     ) -> Self {
+        handle_weak.unwrap().set_seed(seed.to_string().into());
+        show_maze_analysis(searcher.maze(), None, &handle_weak.unwrap());
+
         initialize_maze_drawing(searcher.maze(), handle_weak.clone()).unwrap();
+        redraw_obstacles(
+            searcher.maze(),
+            None,
+            searcher.time_step(),
+            handle_weak.clone(),
+        )
+        .unwrap();
 
         let searcher = Arc::new(Mutex::new(searcher));
+        let generator = Arc::new(Mutex::new(None));
         let task = Arc::new(Mutex::new(None));
-        let visualizer = Self { searcher, task };
+        let path_cycler = Arc::new(Mutex::new(None));
+        let visualizer = Self {
+            searcher,
+            generator,
+            task,
+            path_cycler,
+            seed: Arc::new(Mutex::new(seed)),
+            app_config: Arc::new(Mutex::new(app_config)),
+        };
 
         // Set the process when each button is pressed.
         visualizer.set_advance_callback(handle_weak.clone());
         visualizer.set_play_pause_callback(handle_weak.clone());
         visualizer.set_reset_callback(handle_weak.clone());
         visualizer.set_change_callback(handle_weak.clone());
-        visualizer.set_select_algorithm_callback(handle_weak);
+        visualizer.set_settings_callback(handle_weak.clone());
+        visualizer.set_seed_callback(handle_weak.clone());
+        visualizer.set_select_algorithm_callback(handle_weak.clone());
+        visualizer.set_episodes_per_step_callback(handle_weak.clone());
+        visualizer.set_next_path_callback(handle_weak.clone());
+        visualizer.set_count_paths_callback(handle_weak.clone());
+        visualizer.set_junction_graph_callback(handle_weak.clone());
+        visualizer.set_cell_pressed_callback(handle_weak);
 
         visualizer
     }